@@ -3,18 +3,18 @@ pub mod names;
 use names as nm;
 
 use crate::{
-    errors::{AParserError, FileLocation},
+    errors::{codes, suggest, AParserError, FileLocation, Note},
     lexer::tokens::{Operator, Token, TokenType},
     parser::{
         parse_operand_block::{OperandExpression, OperandExpressionValue},
-        Call, Object, ObjectType, Program, Term, TermBlock, Type,
+        Call, Import, Object, ObjectType, Program, ReplEntry, Term, TermBlock, Type,
     },
 };
 
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
-    fmt::Debug,
+    collections::HashMap,
+    fmt::{Debug, Display},
     iter::zip,
     rc::Rc,
 };
@@ -75,7 +75,17 @@ impl GlobalData {
                 (nm::F_GTEQ, nm::BOOL, &[nm::INT]),
                 (nm::F_LT, nm::BOOL, &[nm::INT]),
                 (nm::F_LTEQ, nm::BOOL, &[nm::INT]),
+                (nm::F_NOT, nm::INT, &[]),
+                (nm::F_BAND, nm::INT, &[nm::INT]),
+                (nm::F_BOR, nm::INT, &[nm::INT]),
+                (nm::F_XOR, nm::INT, &[nm::INT]),
+                (nm::F_SHL, nm::INT, &[nm::INT]),
+                (nm::F_SHR, nm::INT, &[nm::INT]),
+                (nm::F_ABS, nm::INT, &[]),
+                (nm::F_MIN, nm::INT, &[nm::INT]),
+                (nm::F_MAX, nm::INT, &[nm::INT]),
             ],
+            &[],
             gc,
         );
         new.null_type = new.add_root_struct(
@@ -88,6 +98,7 @@ impl GlobalData {
                 (nm::F_BOOL, nm::BOOL, &[]),
                 (nm::F_EQ, nm::BOOL, &[nm::NULL]),
             ],
+            &[],
             gc,
         );
         new.float_type = new.add_root_struct(
@@ -110,6 +121,7 @@ impl GlobalData {
                 (nm::F_LT, nm::BOOL, &[nm::FLOAT]),
                 (nm::F_LTEQ, nm::BOOL, &[nm::FLOAT]),
             ],
+            &[],
             gc,
         );
         new.bool_type = new.add_root_struct(
@@ -121,9 +133,10 @@ impl GlobalData {
                 (nm::F_BOOL, nm::BOOL, &[]),
                 (nm::F_NEW, nm::INT, &[]),
                 (nm::F_NOT, nm::BOOL, &[]),
-                (nm::F_AND, nm::BOOL, &[]),
-                (nm::F_OR, nm::BOOL, &[]),
+                (nm::F_AND, nm::BOOL, &[nm::BOOL]),
+                (nm::F_OR, nm::BOOL, &[nm::BOOL]),
             ],
+            &[],
             gc,
         );
         new.string_type = new.add_root_struct(
@@ -138,34 +151,155 @@ impl GlobalData {
                 (nm::F_ADD, nm::STRING, &[nm::STRING]),
                 (nm::F_MOD, nm::STRING, &[nm::STRING]),
                 (nm::F_EQ, nm::BOOL, &[nm::STRING]),
+                (nm::F_GT, nm::BOOL, &[nm::STRING]),
+                (nm::F_GTEQ, nm::BOOL, &[nm::STRING]),
+                (nm::F_LT, nm::BOOL, &[nm::STRING]),
+                (nm::F_LTEQ, nm::BOOL, &[nm::STRING]),
+                (nm::F_SPLIT, nm::STRING_ARRAY, &[nm::STRING]),
+                (nm::F_TRIM, nm::STRING, &[]),
+                (nm::F_SUBSTR, nm::STRING, &[nm::INT, nm::INT]),
+                (nm::F_FIND, nm::INT, &[nm::STRING]),
+                (nm::F_REPLACE, nm::STRING, &[nm::STRING, nm::STRING]),
+                (nm::F_STARTSWITH, nm::BOOL, &[nm::STRING]),
+                (nm::F_ENDSWITH, nm::BOOL, &[nm::STRING]),
+                (nm::F_UPPER, nm::STRING, &[]),
+                (nm::F_LOWER, nm::STRING, &[]),
+                (nm::F_CHARS, nm::STRING_ARRAY, &[]),
+                (nm::F_REPEAT, nm::STRING, &[nm::INT]),
             ],
+            &[],
             gc,
         );
 
-        new.add_root_function(nm::F_READLN, new.string_type.clone(), gc);
+        new.add_root_struct(
+            nm::PROCESS,
+            &[],
+            &[
+                (nm::F_STDOUT, nm::STRING),
+                (nm::F_STDERR, nm::STRING),
+                (nm::F_STATUS, nm::INT),
+            ],
+            gc,
+        );
+
+        new.add_root_function(nm::F_READLN, nm::STRING, &[], gc);
+        new.add_root_function(nm::F_EXIT, nm::NULL, &[nm::INT], gc);
+
+        // Environment and subprocesses
+        new.add_root_function(nm::F_GETENV, nm::STRING, &[nm::STRING], gc);
+        new.add_root_function(nm::F_HASENV, nm::BOOL, &[nm::STRING], gc);
+        new.add_root_function(nm::F_SETENV, nm::NULL, &[nm::STRING, nm::STRING], gc);
+        new.add_root_function(nm::F_RUN, nm::PROCESS, &[nm::STRING, nm::STRING_ARRAY], gc);
+
+        // Math
+        new.add_root_function(nm::F_SQRT, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_SIN, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_COS, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_TAN, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_ATAN2, nm::FLOAT, &[nm::FLOAT, nm::FLOAT], gc);
+        new.add_root_function(nm::F_LOG, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_ABS, nm::FLOAT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_FLOOR, nm::INT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_CEIL, nm::INT, &[nm::FLOAT], gc);
+        new.add_root_function(nm::F_MIN, nm::FLOAT, &[nm::FLOAT, nm::FLOAT], gc);
+        new.add_root_function(nm::F_MAX, nm::FLOAT, &[nm::FLOAT, nm::FLOAT], gc);
+        new.add_root_function(
+            nm::F_CLAMP,
+            nm::FLOAT,
+            &[nm::FLOAT, nm::FLOAT, nm::FLOAT],
+            gc,
+        );
+
+        // File system
+        new.add_root_struct(
+            nm::FILE_RESULT,
+            &[],
+            &[(nm::F_OK, nm::BOOL), (nm::F_ERROR, nm::STRING)],
+            gc,
+        );
+        new.add_root_struct(
+            nm::TEXT_RESULT,
+            &[],
+            &[
+                (nm::F_OK, nm::BOOL),
+                (nm::F_VALUE, nm::STRING),
+                (nm::F_ERROR, nm::STRING),
+            ],
+            gc,
+        );
+        new.add_root_struct(
+            nm::LIST_RESULT,
+            &[],
+            &[
+                (nm::F_OK, nm::BOOL),
+                (nm::F_VALUE, nm::STRING_ARRAY),
+                (nm::F_ERROR, nm::STRING),
+            ],
+            gc,
+        );
+
+        new.add_root_function(nm::F_READFILE, nm::TEXT_RESULT, &[nm::STRING], gc);
+        new.add_root_function(nm::F_READLINES, nm::LIST_RESULT, &[nm::STRING], gc);
+        new.add_root_function(
+            nm::F_WRITEFILE,
+            nm::FILE_RESULT,
+            &[nm::STRING, nm::STRING],
+            gc,
+        );
+        new.add_root_function(
+            nm::F_APPENDFILE,
+            nm::FILE_RESULT,
+            &[nm::STRING, nm::STRING],
+            gc,
+        );
+        new.add_root_function(nm::F_EXISTS, nm::BOOL, &[nm::STRING], gc);
+        new.add_root_function(nm::F_LISTDIR, nm::LIST_RESULT, &[nm::STRING], gc);
+        new.add_root_function(nm::F_DELETEFILE, nm::FILE_RESULT, &[nm::STRING], gc);
 
         return new;
     }
 
     fn create_forward_ref(&mut self, name: &str) -> Rc<RefCell<AType>> {
-        AType::from_type_nyd(
-            &Type::Object {
+        AType::from_type_nyd(&GlobalData::root_type(name), self)
+    }
+
+    // Root signatures name their types, with a trailing "[]" for arrays
+    fn root_type(name: &str) -> Type {
+        match name.strip_suffix("[]") {
+            Some(element) => Type::Array {
+                _type: Box::new(GlobalData::root_type(element)),
+                location: FileLocation::None,
+            },
+            None => Type::Object {
                 object: Object {
                     loc: FileLocation::None,
                     kind: ObjectType::Identity(name.to_owned()),
                     sub: None,
                 },
             },
-            self,
-        )
+        }
     }
 
     fn add_root_struct(
         &mut self,
         name: &str,
         funcs: &[(&str, &str, &[&str])],
+        fields: &[(&str, &str)],
         gc: &mut GlobalCounter,
     ) -> Rc<AStruct> {
+        let mut a_fields = HashMap::new();
+        for (field_name, field_type) in fields {
+            let _type = self.create_forward_ref(field_type);
+            let _type = _type.borrow().to_type_instance_nyd(self);
+
+            let a_field = AVarDef {
+                name: field_name.to_string(),
+                _type,
+            };
+
+            a_fields.insert(field_name.to_string(), a_field);
+        }
+
         let mut a_funcs = HashMap::new();
         for (func_name, func_return, func_args) in funcs {
             let mut args = Vec::new();
@@ -192,23 +326,41 @@ impl GlobalData {
 
         let a_struct = Rc::new(AStruct {
             name: name.to_string(),
-            fields: HashMap::new(),
+            fields: a_fields,
             methods: a_funcs,
             root: true,
+            loc: FileLocation::None,
         });
 
         self.structs.insert(name.to_string(), a_struct.clone());
         return a_struct;
     }
 
-    fn add_root_function(&mut self, name: &str, returntype: Rc<AStruct>, gc: &mut GlobalCounter) {
+    fn add_root_function(
+        &mut self,
+        name: &str,
+        returntype: &str,
+        args: &[&str],
+        gc: &mut GlobalCounter,
+    ) {
+        let mut a_args = Vec::new();
+        for arg in args {
+            let a_arg = AVarDef {
+                name: String::new(),
+                _type: self.create_forward_ref(arg),
+            };
+
+            a_args.push(a_arg);
+        }
+
+        let returntype = self.create_forward_ref(returntype);
         self.functions.insert(
             name.to_string(),
             AFunc {
                 name: name.to_string(),
-                returntype: AType::from_astruct(returntype),
+                returntype,
                 block: AFuncBlock::Internal,
-                args: Vec::new(),
+                args: a_args,
                 loc: FileLocation::None,
                 uid: gc.next(),
             }
@@ -226,9 +378,10 @@ impl GlobalData {
             None => match self.functions.get(id) {
                 Some(some) => Ok(RefCell::new(AType::FuncDefRef(some.clone())).into()),
                 None => {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("No object of name {} exists.", id),
                         loc.clone(),
+                        codes::T0001,
                     ))
                 }
             },
@@ -261,15 +414,41 @@ impl<'a> DataScope<'a> {
         gd: &GlobalData,
         loc: &FileLocation,
     ) -> Result<Rc<RefCell<AType>>, AParserError> {
+        if let Some(some) = self.resolve_var(id) {
+            return Ok(some);
+        }
+
+        match gd.resolve_id(id, loc) {
+            Ok(ok) => return Ok(ok),
+            Err(mut err) => {
+                let globals = gd.structs.keys().chain(gd.functions.keys());
+                let names = self.var_names().into_iter().chain(globals);
+                err.0 += &suggest::did_you_mean(id, names.map(|name| name.as_str()));
+                return Err(err);
+            }
+        }
+    }
+
+    fn resolve_var(&self, id: &String) -> Option<Rc<RefCell<AType>>> {
         match self.vars.get(id) {
-            Some(some) => Ok(some.clone()),
+            Some(some) => Some(some.clone()),
             None => match &self.parent {
-                Some(parent) => parent.resolve_id(id, gd, loc),
-                None => gd.resolve_id(id, loc),
+                Some(parent) => parent.resolve_var(id),
+                None => None,
             },
         }
     }
 
+    // Names of the variables in this scope and every scope around it
+    fn var_names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.vars.keys().collect();
+        if let Some(parent) = &self.parent {
+            names.append(&mut parent.var_names());
+        }
+
+        return names;
+    }
+
     fn resolve_type(
         &self,
         _type: &Type,
@@ -310,6 +489,13 @@ impl<'a> DataScope<'a> {
 pub struct AProgram {
     pub structs: Vec<Rc<AStruct>>,
     pub functions: Vec<Rc<AFunc>>,
+    pub imports: Vec<Import>,
+}
+
+#[derive(Debug)]
+pub enum AReplEntry {
+    Terms(ATermBlock),
+    Expression(AOperandExpression),
 }
 
 #[derive(Debug)]
@@ -328,6 +514,7 @@ pub enum ATerm {
         name: String,
         _vartype: Rc<RefCell<AType>>,
         value: AOperandExpression,
+        loc: FileLocation,
     },
     Return {
         value: AOperandExpression,
@@ -348,9 +535,11 @@ pub enum ATerm {
         counter: String,
         conditional: AOperandExpression,
         block: ATermBlock,
+        // Location of the counter
+        loc: FileLocation,
     },
-    Break,
-    Continue,
+    Break(FileLocation),
+    Continue(FileLocation),
 }
 
 pub enum AType {
@@ -407,9 +596,10 @@ impl AType {
                 .borrow()
                 .structdefref_is_instance(&object.borrow(), loc),
             (AType::ArrayObject(..), AType::StructObject(..)) => Ok(false),
-            (AType::StructDefRef(..), AType::StructDefRef(astruct)) => Err(AParserError(
+            (AType::StructDefRef(..), AType::StructDefRef(astruct)) => Err(AParserError::new(
                 format!("{:?} is a type definition not an instance", astruct.name),
                 loc.clone(),
+                codes::T0012,
             )),
             _ => panic!(
                 "Bad StructDefRef Is Instance Check:\n - self: {:?}\n - inst: {:?}",
@@ -477,6 +667,18 @@ impl AType {
     }
 }
 
+impl Display for AType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ArrayObject(arg0) => write!(f, "{}[]", arg0.borrow()),
+            Self::StructObject(arg0) => write!(f, "{}", arg0.name),
+            Self::StructDefRef(arg0) => write!(f, "{} definition", arg0.name),
+            Self::FuncDefRef(arg0) => write!(f, "func {}", arg0.name),
+            Self::NotYetDefined(..) => write!(f, "{:?}", self),
+        }
+    }
+}
+
 impl Debug for AType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -546,9 +748,10 @@ impl AObject {
                 AObject::from_object_sub_struct(object, parent_type, ds, gd, gc)
             }
             AType::StructDefRef(_) => {
-                return Err(AParserError(
+                return Err(AParserError::new(
                     format!("Cannot get field or method on struct definition"),
                     object.loc.clone(),
+                    codes::T0011,
                 ))
             }
             AType::FuncDefRef(_) => {
@@ -567,15 +770,17 @@ impl AObject {
     ) -> Result<AObject, AParserError> {
         let call = match &object.kind {
             ObjectType::Identity(_) => {
-                return Err(AParserError(
+                return Err(AParserError::new(
                     format!("Cannot get field on function definition"),
                     object.loc.clone(),
+                    codes::T0011,
                 ))
             }
             ObjectType::Index(_) => {
-                return Err(AParserError(
+                return Err(AParserError::new(
                     format!("Cannot index function definition"),
                     object.loc.clone(),
+                    codes::T0012,
                 ))
             }
             ObjectType::Call(call) => call,
@@ -595,6 +800,8 @@ impl AObject {
                     call.args.len(),
                 ),
                 object.loc.clone(),
+                codes::T0008,
+                declared_here(func),
             ));
         }
 
@@ -610,6 +817,8 @@ impl AObject {
                 return Err(AParserError(
                     format!("Missmatched arg type."),
                     a_arg.loc.clone(),
+                    codes::T0004,
+                    declared_here(func),
                 ));
             }
 
@@ -652,34 +861,79 @@ impl AObject {
 
         match &object.kind {
             ObjectType::Identity(id) => {
+                let arg = |name: &str, _type: Rc<RefCell<AType>>| AVarDef {
+                    name: String::from(name),
+                    _type,
+                };
+                let int = || AType::from_astruct(gd.int_type.clone());
+                let null = || AType::from_astruct(gd.null_type.clone());
+                let element = || arr_type.borrow().to_type_defref();
+                let array = || RefCell::new(AType::ArrayObject(element())).into();
+
                 let (returntype, args) = match id.as_str() {
-                    nm::F_INDEX => (
-                        arr_type.borrow().to_type_defref(),
-                        vec![AVarDef {
-                            name: String::from("idx"),
-                            _type: AType::from_astruct(gd.int_type.clone()),
-                        }],
-                    ),
-                    nm::F_APPEND => (
-                        AType::from_astruct(gd.null_type.clone()),
-                        vec![AVarDef {
-                            name: String::from("idx"),
-                            _type: arr_type.borrow().to_type_defref(),
-                        }],
+                    nm::F_INDEX => (element(), vec![arg("idx", int())]),
+                    nm::F_APPEND => (null(), vec![arg("value", element())]),
+                    nm::F_REMOVE => (element(), vec![arg("idx", int())]),
+                    nm::F_LEN => (int(), vec![]),
+                    nm::F_INSERT => (null(), vec![arg("idx", int()), arg("value", element())]),
+                    nm::F_POP => (element(), vec![]),
+                    nm::F_SLICE => (array(), vec![arg("start", int()), arg("end", int())]),
+                    nm::F_REVERSE => (null(), vec![]),
+                    nm::F_CONTAINS => (
+                        AType::from_astruct(gd.bool_type.clone()),
+                        vec![arg("value", element())],
                     ),
-                    nm::F_REMOVE => (
-                        arr_type.borrow().to_type_defref(),
-                        vec![AVarDef {
-                            name: String::from("idx"),
-                            _type: AType::from_astruct(gd.int_type.clone()),
-                        }],
-                    ),
-                    nm::F_LEN => (AType::from_astruct(gd.int_type.clone()), vec![]),
+                    nm::F_INDEXOF => (int(), vec![arg("value", element())]),
+                    nm::F_EXTEND => (null(), vec![arg("other", array())]),
+                    nm::F_CLEAR => (null(), vec![]),
+                    nm::F_SORT => {
+                        let comparable = [&gd.int_type, &gd.float_type, &gd.string_type]
+                            .iter()
+                            .any(|x| AStruct::astruct_type_object_match(x, &arr_type.borrow()));
+
+                        if !comparable {
+                            return Err(AParserError::new(
+                                format!(
+                                    "{} requires elements of type int, float or str, found type {:?}.",
+                                    id,
+                                    arr_type.borrow()
+                                ),
+                                object.loc.clone(),
+                                codes::T0017,
+                            ));
+                        }
+
+                        (null(), vec![])
+                    }
+                    nm::F_JOIN => {
+                        if !AStruct::astruct_type_object_match(&gd.string_type, &arr_type.borrow())
+                        {
+                            return Err(AParserError::new(
+                                format!(
+                                    "{} requires elements of type str, found type {:?}.",
+                                    id,
+                                    arr_type.borrow()
+                                ),
+                                object.loc.clone(),
+                                codes::T0017,
+                            ));
+                        }
+
+                        (
+                            AType::from_astruct(gd.string_type.clone()),
+                            vec![arg("sep", AType::from_astruct(gd.string_type.clone()))],
+                        )
+                    }
 
                     _ => {
-                        return Err(AParserError(
-                            format!("{} is not a recognized method of vectors.", id),
+                        return Err(AParserError::new(
+                            format!(
+                                "{} is not a recognized method of vectors.{}",
+                                id,
+                                suggest::did_you_mean(id, nm::ARRAY_METHODS)
+                            ),
                             object.loc.clone(),
+                            codes::T0011,
                         ))
                     }
                 };
@@ -714,9 +968,10 @@ impl AObject {
                     loc: object.loc.clone(),
                 });
             }
-            ObjectType::Call(_) => Err(AParserError(
+            ObjectType::Call(_) => Err(AParserError::new(
                 format!("Cannot directly call vector."),
                 object.loc.clone(),
+                codes::T0012,
             )),
             ObjectType::Index(operand_expression) => {
                 let name = nm::F_INDEX.to_string();
@@ -794,12 +1049,22 @@ impl AObject {
                             Some(parent_type),
                         ),
                         None => {
-                            return Err(AParserError(
+                            return Err(AParserError::new(
                                 format!(
-                                    "Struct object {} has no field or method {}.",
-                                    astruct.name, id
+                                    "Struct object {} has no field or method {}.{}",
+                                    astruct.name,
+                                    id,
+                                    suggest::did_you_mean(
+                                        id,
+                                        astruct
+                                            .fields
+                                            .keys()
+                                            .chain(astruct.methods.keys())
+                                            .map(|name| name.as_str())
+                                    )
                                 ),
                                 object.loc.clone(),
+                                codes::T0011,
                             ));
                         }
                     },
@@ -812,18 +1077,20 @@ impl AObject {
                 )
             }
             ObjectType::Call(..) => {
-                return Err(AParserError(
+                return Err(AParserError::new(
                     format!("Cannot directly call function on struct"),
                     object.loc.clone(),
+                    codes::T0012,
                 ))
             }
             ObjectType::Index(idx) => {
                 let func = match astruct.methods.get(nm::F_INDEX) {
                     Some(func) => func,
                     None => {
-                        return Err(AParserError(
+                        return Err(AParserError::new(
                             format!("{} has no method {}", astruct.name, nm::F_INDEX),
                             object.loc.clone(),
+                            codes::T0011,
                         ))
                     }
                 };
@@ -866,6 +1133,43 @@ impl AObject {
         });
     }
 
+    fn check_assignable(&self) -> Result<(), AParserError> {
+        let sub = match (&self.kind, &*self._type.borrow()) {
+            (AObjectType::Identity(_), AType::FuncDefRef(func)) => match (&func.block, &self.sub) {
+                (AFuncBlock::InternalArray, Some(call)) if func.name == nm::F_INDEX => {
+                    call.sub.clone()
+                }
+                _ => {
+                    return Err(AParserError::new(
+                        format!("Cannot assign to function or method {}.", func.name),
+                        self.loc.clone(),
+                        codes::T0013,
+                    ))
+                }
+            },
+            (AObjectType::Identity(id), AType::StructDefRef(_)) => {
+                return Err(AParserError::new(
+                    format!("Cannot assign to struct definition {}.", id),
+                    self.loc.clone(),
+                    codes::T0013,
+                ))
+            }
+            (AObjectType::Identity(_), _) => self.sub.clone(),
+            (AObjectType::Call(_), _) => {
+                return Err(AParserError::new(
+                    format!("Only array indexes can be assigned to."),
+                    self.loc.clone(),
+                    codes::T0013,
+                ))
+            }
+        };
+
+        match sub {
+            Some(sub) => sub.check_assignable(),
+            None => Ok(()),
+        }
+    }
+
     fn bottom_type(&self) -> Rc<RefCell<AType>> {
         match &self.sub {
             Some(some) => some.bottom_type(),
@@ -908,6 +1212,19 @@ pub struct AFunc {
     pub uid: u32,
 }
 
+// Point to where a function was declared, unless it is built in
+fn declared_here(func: &AFunc) -> Vec<Note> {
+    match func.loc {
+        FileLocation::None => return Vec::new(),
+        _ => {
+            return vec![Note(
+                format!("{} declared here", func.name),
+                func.loc.clone(),
+            )]
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ALiteral {
     Int(i32),
@@ -933,6 +1250,11 @@ pub struct AOperandExpression {
     pub loc: FileLocation,
     pub value: AOperandExpressionValue,
 }
+impl AOperandExpression {
+    pub fn get_type(&self) -> Rc<RefCell<AType>> {
+        self._type.clone()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum AOperandExpressionValue {
@@ -940,12 +1262,21 @@ pub enum AOperandExpressionValue {
         left: Box<AOperandExpression>,
         right: AObject,
     },
+    And {
+        left: Box<AOperandExpression>,
+        right: Box<AOperandExpression>,
+    },
+    Or {
+        left: Box<AOperandExpression>,
+        right: Box<AOperandExpression>,
+    },
     Object(AObject),
     Literal(ALiteral),
     Create {
         _type: Rc<RefCell<AType>>,
         args: Vec<AOperandExpression>,
     },
+    Array(Vec<AOperandExpression>),
 }
 
 #[derive(Debug)]
@@ -954,6 +1285,7 @@ pub struct AStruct {
     pub fields: HashMap<String, AVarDef>,
     pub methods: HashMap<String, Rc<AFunc>>,
     pub root: bool,
+    pub loc: FileLocation,
 }
 impl AStruct {
     fn tmp_empty_root() -> Self {
@@ -962,6 +1294,7 @@ impl AStruct {
             fields: HashMap::new(),
             methods: HashMap::new(),
             root: true,
+            loc: FileLocation::None,
         }
     }
 
@@ -993,6 +1326,15 @@ impl ReturnOpts {
     }
 }
 
+// Value of a built-in constant such as @pi
+fn constant(id: &str) -> Option<ALiteral> {
+    match id {
+        nm::C_PI => return Some(ALiteral::Float(std::f32::consts::PI)),
+        nm::C_E => return Some(ALiteral::Float(std::f32::consts::E)),
+        _ => return None,
+    }
+}
+
 fn aparse_operandexpression(
     operand_expression: &OperandExpression,
     ds: &DataScope,
@@ -1028,6 +1370,42 @@ fn aparse_operandexpression(
                 loc: operand.1.clone(),
             })
         }
+        OperandExpressionValue::Binary {
+            operand,
+            left,
+            right,
+        } if matches!(operand.0, TokenType::Operator(Operator::And | Operator::Or)) => {
+            let left = aparse_operandexpression(&left, ds, gd, gc)?;
+            let right = aparse_operandexpression(&right, ds, gd, gc)?;
+
+            for side in [&left, &right] {
+                if !AStruct::astruct_type_object_match(&gd.bool_type, &side._type.borrow()) {
+                    return Err(AParserError::new(
+                        format!(
+                            "Operands of {} must be of type bool, found type {:?}",
+                            operand.0,
+                            &side._type.borrow()
+                        ),
+                        side.loc.clone(),
+                        codes::T0005,
+                    ));
+                }
+            }
+
+            let (left, right) = (Box::new(left), Box::new(right));
+            Ok(AOperandExpression {
+                _type: AType::from_astruct(gd.bool_type.clone())
+                    .borrow()
+                    .to_type_instance(),
+                value: match operand.0 {
+                    TokenType::Operator(Operator::And) => {
+                        AOperandExpressionValue::And { left, right }
+                    }
+                    _ => AOperandExpressionValue::Or { left, right },
+                },
+                loc: operand.1.clone(),
+            })
+        }
         OperandExpressionValue::Binary {
             operand,
             left,
@@ -1045,8 +1423,11 @@ fn aparse_operandexpression(
                 TokenType::Operator(Operator::GreaterOrEqual) => nm::F_GTEQ,
                 TokenType::Operator(Operator::Less) => nm::F_LT,
                 TokenType::Operator(Operator::LessOrEqual) => nm::F_LTEQ,
-                TokenType::Operator(Operator::And) => nm::F_AND,
-                TokenType::Operator(Operator::Or) => nm::F_OR,
+                TokenType::Operator(Operator::BitAnd) => nm::F_BAND,
+                TokenType::Operator(Operator::BitOr) => nm::F_BOR,
+                TokenType::Operator(Operator::BitXor) => nm::F_XOR,
+                TokenType::Operator(Operator::ShiftLeft) => nm::F_SHL,
+                TokenType::Operator(Operator::ShiftRight) => nm::F_SHR,
                 _ => panic!(),
             };
             let left = aparse_operandexpression(&left, ds, gd, gc)?;
@@ -1095,6 +1476,33 @@ fn aparse_operandexpression(
                 loc: literal.1.clone(),
             });
         }
+        OperandExpressionValue::Object(Object {
+            kind: ObjectType::Identity(id),
+            sub,
+            loc,
+        }) if constant(id).is_some() => {
+            let a_literal = constant(id).unwrap();
+            let literal = AOperandExpression {
+                _type: AType::from_aliteral(&a_literal, gd),
+                value: AOperandExpressionValue::Literal(a_literal),
+                loc: loc.clone(),
+            };
+
+            let sub = match sub {
+                Some(sub) => sub,
+                None => return Ok(literal),
+            };
+
+            let right = AObject::from_object_sub(&sub, &literal._type.borrow(), ds, gd, gc)?;
+            return Ok(AOperandExpression {
+                _type: right.bottom_type(),
+                value: AOperandExpressionValue::Dot {
+                    left: Box::new(literal),
+                    right,
+                },
+                loc: loc.clone(),
+            });
+        }
         OperandExpressionValue::Object(obj) => {
             let a_object = AObject::from_object(&obj, ds, gd, gc)?;
             let loc = a_object.loc.clone();
@@ -1105,15 +1513,113 @@ fn aparse_operandexpression(
                 loc,
             });
         }
+        OperandExpressionValue::Array(values) => {
+            let mut a_values = Vec::new();
+            for value in values {
+                a_values.push(aparse_operandexpression(value, ds, gd, gc)?);
+            }
+
+            let element_type = match a_values.first() {
+                Some(first) => first._type.clone(),
+                None => {
+                    return Err(AParserError::new(
+                        format!("Cannot infer the type of an empty array literal. Use $() to create an empty array."),
+                        operand_expression.1.clone(),
+                        codes::T0015,
+                    ))
+                }
+            };
+
+            if let AType::StructDefRef(..) | AType::FuncDefRef(..) = *element_type.borrow() {
+                return Err(AParserError::new(
+                    format!("{:?} is not an instance.", element_type.borrow()),
+                    a_values[0].loc.clone(),
+                    codes::T0012,
+                ));
+            }
+
+            for value in &a_values[1..] {
+                if !element_type
+                    .borrow()
+                    .to_type_defref()
+                    .borrow()
+                    .structdefref_is_instance(&value._type.borrow(), &value.loc)?
+                {
+                    return Err(AParserError::new(
+                        format!(
+                            "Array literal elements must all be of type {:?}, found type {:?}.",
+                            element_type.borrow(),
+                            value._type.borrow()
+                        ),
+                        value.loc.clone(),
+                        codes::T0007,
+                    ));
+                }
+            }
+
+            return Ok(AOperandExpression {
+                _type: RefCell::new(AType::ArrayObject(element_type)).into(),
+                value: AOperandExpressionValue::Array(a_values),
+                loc: operand_expression.1.clone(),
+            });
+        }
         OperandExpressionValue::Create(create) => {
             let _type = ds.resolve_type(&create.kind, gd, gc)?;
             let new_method = match *_type.borrow() {
+                AType::StructDefRef(ref rc) if rc.root && !rc.methods.contains_key(nm::F_NEW) => {
+                    return Err(AParserError::new(
+                        format!("{} can not be created with $().", rc.name),
+                        operand_expression.1.clone(),
+                        codes::T0016,
+                    ))
+                }
                 AType::StructDefRef(ref rc) => rc.methods.get(nm::F_NEW).cloned(),
                 AType::ArrayObject(..) => None,
                 _ => panic!(),
             };
 
-            let args = if let Some(new_method) = new_method {
+            let args = if let AType::ArrayObject(ref element_type) = *_type.borrow() {
+                let mut args = Vec::new();
+                for arg in &create.args.args {
+                    args.push(aparse_operandexpression(&arg, ds, gd, gc)?);
+                }
+
+                match &args[..] {
+                    [] => {}
+                    [size, fill] => {
+                        if !AStruct::astruct_type_object_match(&gd.int_type, &size._type.borrow()) {
+                            return Err(AParserError::new(
+                                format!("Array size must be of type int."),
+                                size.loc.clone(),
+                                codes::T0007,
+                            ));
+                        }
+
+                        if !element_type
+                            .borrow()
+                            .structdefref_is_instance(&fill._type.borrow(), &fill.loc)?
+                        {
+                            return Err(AParserError::new(
+                                format!(
+                                    "Array fill value must be of type {:?}.",
+                                    element_type.borrow()
+                                ),
+                                fill.loc.clone(),
+                                codes::T0007,
+                            ));
+                        }
+                    }
+                    _ => {
+                        return Err(AParserError::new(
+                            format!("Array creation takes either no arguments or a size and a fill value: $(size, fill)."),
+                            operand_expression.1.clone(),
+                            codes::T0008,
+                        ))
+                    }
+                }
+
+                args
+            } else if let Some(new_method) = new_method {
                 let mut args = Vec::new();
 
                 for (arg, argdef) in zip(&create.args.args, &new_method.args) {
@@ -1124,9 +1630,10 @@ fn aparse_operandexpression(
                         .borrow()
                         .structdefref_is_instance(&arg._type.borrow(), &arg.loc)?
                     {
-                        return Err(AParserError(
+                        return Err(AParserError::new(
                             format!("Arg to {} was incorrect type.", nm::F_NEW),
                             arg.loc.clone(),
+                            codes::T0004,
                         ));
                     }
 
@@ -1134,22 +1641,24 @@ fn aparse_operandexpression(
                 }
 
                 if new_method.args.len() != create.args.args.len() {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Invalid number of args to {}.", nm::F_NEW),
                         operand_expression.1.clone(),
+                        codes::T0008,
                     ));
                 }
 
                 args
             } else {
                 if create.args.args.len() > 0 {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!(
                             "{:?} has no explicit {} function, therefore $() should not take any arguments.",
                             _type.borrow(),
                             nm::F_NEW
                         ),
                         create.args.args[0].1.clone(),
+                        codes::T0008,
                     ));
                 }
 
@@ -1189,9 +1698,10 @@ fn aparse_termblock(
                     .borrow()
                     .structdefref_is_instance(&value._type.borrow(), &value.loc)?
                 {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         "Cannot print non string objects.".to_string(),
                         value.loc.clone(),
+                        codes::T0006,
                     ));
                 }
 
@@ -1201,6 +1711,7 @@ fn aparse_termblock(
                 name,
                 vartype,
                 value,
+                loc: var_loc,
             } => {
                 let a_type = ds.resolve_type(vartype, gd, gc)?;
                 let a_value = aparse_operandexpression(value, &ds, gd, gc)?;
@@ -1209,9 +1720,10 @@ fn aparse_termblock(
                     .borrow()
                     .structdefref_is_instance(&a_value._type.borrow(), &a_value.loc)?
                 {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Value type does not match var type."),
                         a_value.loc.clone(),
+                        codes::T0003,
                     ));
                 }
 
@@ -1222,6 +1734,7 @@ fn aparse_termblock(
                     name: name.to_owned(),
                     _vartype: vartype,
                     value: a_value,
+                    loc: var_loc.clone(),
                 }
             }
             Term::Return { value } => {
@@ -1238,17 +1751,19 @@ fn aparse_termblock(
                         .borrow()
                         .is_nulldef(gd)
                     {
-                        return Err(AParserError(
+                        return Err(AParserError::new(
                             format!("Incorrect type retuned from function."),
                             value.loc.clone(),
+                            codes::T0009,
                         ));
                     }
                 }
 
                 if term_idx != num_terms - 1 {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Return must be last term in block."),
                         value.loc.clone(),
+                        codes::T0010,
                     ));
                 }
 
@@ -1299,11 +1814,13 @@ fn aparse_termblock(
 
                 let value = aparse_operandexpression(&operand_expression, &ds, gd, gc)?;
                 let var = AObject::from_object(var, &ds, gd, gc)?;
+                var.check_assignable()?;
 
                 if !AType::instance_type_match(&var.bottom_type().borrow(), &value._type.borrow()) {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Missmatched types (2)"),
                         var.loc.clone(),
+                        codes::T0003,
                     ));
                 }
 
@@ -1316,12 +1833,13 @@ fn aparse_termblock(
             } => {
                 let conditional = aparse_operandexpression(conditional, &ds, gd, gc)?;
                 if !AStruct::astruct_type_object_match(&gd.bool_type, &conditional._type.borrow()) {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!(
                             "Conditional must be of type bool, found type {:?}",
                             &conditional._type.borrow()
                         ),
                         conditional.loc.clone(),
+                        codes::T0005,
                     ));
                 }
 
@@ -1344,6 +1862,7 @@ fn aparse_termblock(
                 counter,
                 conditional,
                 block,
+                loc: counter_loc,
             } => {
                 let mut ds = ds.child();
 
@@ -1364,25 +1883,28 @@ fn aparse_termblock(
                     counter: counter.to_string(),
                     conditional,
                     block,
+                    loc: counter_loc.clone(),
                 }
             }
             Term::Break(loc) => {
                 if return_opts.loop_returns {
-                    ATerm::Break
+                    ATerm::Break(loc.clone())
                 } else {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Cannot break from outside loop."),
                         loc.clone(),
+                        codes::T0014,
                     ));
                 }
             }
             Term::Continue(loc) => {
                 if return_opts.loop_returns {
-                    ATerm::Continue
+                    ATerm::Continue(loc.clone())
                 } else {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Cannot continue from outside loop."),
                         loc.clone(),
+                        codes::T0014,
                     ));
                 }
             }
@@ -1400,9 +1922,10 @@ fn aparse_termblock(
             Some(Term::Return { .. } | Term::If { .. }) => {}
             _ => {
                 if !return_opts.expected_type.borrow().is_nulldef(gd) {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Not all paths return correct type"),
                         loc.clone(),
+                        codes::T0009,
                     ));
                 }
             }
@@ -1413,20 +1936,84 @@ fn aparse_termblock(
 }
 
 pub fn aparse(program: &Program) -> Result<AProgram, AParserError> {
-    let mut names = HashSet::new();
+    let (a_program, _, _) = aparse_globals(program)?;
+    return Ok(a_program);
+}
+
+// Check a REPL entry against the declared program and the types of the
+// variables bound by earlier entries
+pub fn aparse_repl(
+    program: &Program,
+    vars: &[(String, Type)],
+    entry: &ReplEntry,
+) -> Result<(AProgram, AReplEntry), AParserError> {
+    let (a_program, gd, mut gc) = aparse_globals(program)?;
+
+    let mut ds = DataScope::new();
+    for (name, _type) in vars {
+        let a_type = ds.resolve_type(_type, &gd, &mut gc)?;
+        let a_type = a_type.borrow().to_type_instance();
+        ds.vars.insert(name.clone(), a_type);
+    }
+
+    let a_entry = match entry {
+        // Declarations are already part of the program
+        ReplEntry::Declarations(_) => AReplEntry::Terms(ATermBlock::A { terms: Vec::new() }),
+        ReplEntry::Terms(block) => {
+            let return_opts = ReturnOpts {
+                expected_type: AType::from_astruct(gd.null_type.clone()),
+                loop_returns: false,
+                require_explicit: false,
+            };
+
+            AReplEntry::Terms(aparse_termblock(
+                block,
+                &ds,
+                &gd,
+                &mut gc,
+                &return_opts,
+                &FileLocation::None,
+            )?)
+        }
+        ReplEntry::Expression(expression) => {
+            AReplEntry::Expression(aparse_operandexpression(expression, &ds, &gd, &mut gc)?)
+        }
+    };
+
+    return Ok((a_program, a_entry));
+}
+
+fn aparse_globals(
+    program: &Program,
+) -> Result<(AProgram, GlobalData, GlobalCounter), AParserError> {
+    let mut names: HashMap<&String, &FileLocation> = HashMap::new();
     let mut gc = GlobalCounter::new();
     let mut gd = GlobalData::new(&mut gc);
     let mut structs = Vec::new();
     let mut functions = Vec::new();
 
     for _struct in &program.structs {
-        if names.contains(&_struct.name) {
-            return Err(AParserError(
+        // A struct with the name of a built-in would shadow it
+        if gd.structs.get(&_struct.name).is_some_and(|s| s.root) {
+            return Err(AParserError::new(
+                format!("Struct {} has the name of a built-in struct.", _struct.name),
+                _struct.loc.clone(),
+                codes::T0018,
+            ));
+        }
+
+        if let Some(first) = names.get(&_struct.name) {
+            return Err(AParserError::new(
                 format!("Global object {} has multiple definitions.", _struct.name),
                 _struct.loc.clone(),
+                codes::T0002,
+            )
+            .with_note(
+                format!("{} first defined here", _struct.name),
+                (*first).clone(),
             ));
         } else {
-            names.insert(&_struct.name);
+            names.insert(&_struct.name, &_struct.loc);
         }
 
         let name = _struct.name.clone();
@@ -1477,6 +2064,7 @@ pub fn aparse(program: &Program) -> Result<AProgram, AParserError> {
             fields,
             methods,
             root: false,
+            loc: _struct.loc.clone(),
         });
 
         gd.structs.insert(_struct.name.clone(), a_struct.clone());
@@ -1484,13 +2072,18 @@ pub fn aparse(program: &Program) -> Result<AProgram, AParserError> {
     }
 
     for func in &program.functions {
-        if names.contains(&func.name) {
-            return Err(AParserError(
+        if let Some(first) = names.get(&func.name) {
+            return Err(AParserError::new(
                 format!("Global object {} has multiple definitions.", func.name),
                 func.loc.clone(),
+                codes::T0002,
+            )
+            .with_note(
+                format!("{} first defined here", func.name),
+                (*first).clone(),
             ));
         } else {
-            names.insert(&func.name);
+            names.insert(&func.name, &func.loc);
         }
 
         let returntype = AType::from_type_nyd(&func.returntype, &mut gd);
@@ -1530,9 +2123,10 @@ pub fn aparse(program: &Program) -> Result<AProgram, AParserError> {
 
             match *a_type.borrow() {
                 AType::NotYetDefined(..) => {
-                    return Err(AParserError(
+                    return Err(AParserError::new(
                         format!("Could not find type"),
                         _type.get_location().clone(),
+                        codes::T0012,
                     ))
                 }
                 _ => gd.not_yet_defined.remove(0),
@@ -1607,7 +2201,11 @@ pub fn aparse(program: &Program) -> Result<AProgram, AParserError> {
         }
     }
 
-    let a_program = AProgram { structs, functions };
+    let a_program = AProgram {
+        structs,
+        functions,
+        imports: program.imports.clone(),
+    };
 
-    Ok(a_program)
+    Ok((a_program, gd, gc))
 }
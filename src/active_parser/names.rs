@@ -3,6 +3,11 @@ pub const INT: &str = "int";
 pub const FLOAT: &str = "float";
 pub const NULL: &str = "null";
 pub const STRING: &str = "str";
+pub const STRING_ARRAY: &str = "str[]";
+pub const PROCESS: &str = "process";
+pub const FILE_RESULT: &str = "fileresult";
+pub const TEXT_RESULT: &str = "textresult";
+pub const LIST_RESULT: &str = "listresult";
 
 pub const F_BOOL: &str = "@bool";
 pub const F_INT: &str = "@int";
@@ -24,18 +29,177 @@ pub const F_LTEQ: &str = "@lteq";
 pub const F_NOT: &str = "@not";
 pub const F_AND: &str = "@and";
 pub const F_OR: &str = "@or";
+pub const F_BAND: &str = "@band";
+pub const F_BOR: &str = "@bor";
+pub const F_XOR: &str = "@xor";
+pub const F_SHL: &str = "@shl";
+pub const F_SHR: &str = "@shr";
 pub const F_LEN: &str = "@len";
 pub const F_INDEX: &str = "@idx";
 pub const F_APPEND: &str = "@append";
 pub const F_REMOVE: &str = "@remove";
+pub const F_INSERT: &str = "@insert";
+pub const F_POP: &str = "@pop";
+pub const F_SLICE: &str = "@slice";
+pub const F_REVERSE: &str = "@reverse";
+pub const F_CONTAINS: &str = "@contains";
+pub const F_INDEXOF: &str = "@indexof";
+pub const F_SORT: &str = "@sort";
+pub const F_JOIN: &str = "@join";
+pub const F_EXTEND: &str = "@extend";
+pub const F_CLEAR: &str = "@clear";
+pub const F_SPLIT: &str = "@split";
+pub const F_TRIM: &str = "@trim";
+pub const F_SUBSTR: &str = "@substr";
+pub const F_FIND: &str = "@find";
+pub const F_REPLACE: &str = "@replace";
+pub const F_STARTSWITH: &str = "@startswith";
+pub const F_ENDSWITH: &str = "@endswith";
+pub const F_UPPER: &str = "@upper";
+pub const F_LOWER: &str = "@lower";
+pub const F_CHARS: &str = "@chars";
+pub const F_REPEAT: &str = "@repeat";
+
+// Methods of every array type
+pub const ARRAY_METHODS: [&str; 14] = [
+    F_INDEX, F_APPEND, F_REMOVE, F_LEN, F_INSERT, F_POP, F_SLICE, F_REVERSE, F_CONTAINS, F_INDEXOF,
+    F_EXTEND, F_CLEAR, F_SORT, F_JOIN,
+];
 
 pub const F_READLN: &str = "@readln";
+pub const F_EXIT: &str = "@exit";
+
+pub const F_GETENV: &str = "@getenv";
+pub const F_HASENV: &str = "@hasenv";
+pub const F_SETENV: &str = "@setenv";
+pub const F_RUN: &str = "@run";
+pub const F_STDOUT: &str = "@stdout";
+pub const F_STDERR: &str = "@stderr";
+pub const F_STATUS: &str = "@status";
+
+// Fields of root structs, which the interpreter fills in directly
+pub const ROOT_FIELDS: [&str; 6] = [F_STDOUT, F_STDERR, F_STATUS, F_OK, F_VALUE, F_ERROR];
 pub const F_MAIN: &str = "@main";
 
+pub const F_SQRT: &str = "@sqrt";
+pub const F_SIN: &str = "@sin";
+pub const F_COS: &str = "@cos";
+pub const F_TAN: &str = "@tan";
+pub const F_ATAN2: &str = "@atan2";
+pub const F_LOG: &str = "@log";
+pub const F_ABS: &str = "@abs";
+pub const F_FLOOR: &str = "@floor";
+pub const F_CEIL: &str = "@ceil";
+pub const F_MIN: &str = "@min";
+pub const F_MAX: &str = "@max";
+pub const F_CLAMP: &str = "@clamp";
+pub const C_PI: &str = "@pi";
+pub const C_E: &str = "@e";
+
+pub const F_READFILE: &str = "@readfile";
+pub const F_READLINES: &str = "@readlines";
+pub const F_WRITEFILE: &str = "@writefile";
+pub const F_APPENDFILE: &str = "@appendfile";
+pub const F_EXISTS: &str = "@exists";
+pub const F_LISTDIR: &str = "@listdir";
+pub const F_DELETEFILE: &str = "@deletefile";
+pub const F_OK: &str = "@ok";
+pub const F_VALUE: &str = "@value";
+pub const F_ERROR: &str = "@error";
+
 pub const THIS: &str = "@this";
 
-pub const PREFIX_PROTECTED_NAMES: [&str; 30] = [
-    BOOL, INT, FLOAT, NULL, STRING, F_BOOL, F_INT, F_FLOAT, F_STRING, F_NEW, F_ADD, F_SUB, F_MULT,
-    F_DIV, F_MOD, F_EXP, F_EQ, F_GT, F_GTEQ, F_LT, F_LTEQ, F_NOT, F_AND, F_OR, F_LEN, F_INDEX,
-    F_APPEND, F_REMOVE, F_READLN, THIS,
+pub const PREFIX_PROTECTED_NAMES: [&str; 92] = [
+    BOOL,
+    INT,
+    FLOAT,
+    NULL,
+    STRING,
+    F_BOOL,
+    F_INT,
+    F_FLOAT,
+    F_STRING,
+    F_NEW,
+    F_ADD,
+    F_SUB,
+    F_MULT,
+    F_DIV,
+    F_MOD,
+    F_EXP,
+    F_EQ,
+    F_GT,
+    F_GTEQ,
+    F_LT,
+    F_LTEQ,
+    F_NOT,
+    F_AND,
+    F_OR,
+    F_BAND,
+    F_BOR,
+    F_XOR,
+    F_SHL,
+    F_SHR,
+    F_LEN,
+    F_INDEX,
+    F_APPEND,
+    F_REMOVE,
+    F_INSERT,
+    F_POP,
+    F_SLICE,
+    F_REVERSE,
+    F_CONTAINS,
+    F_INDEXOF,
+    F_SORT,
+    F_JOIN,
+    F_EXTEND,
+    F_CLEAR,
+    F_SPLIT,
+    F_TRIM,
+    F_SUBSTR,
+    F_FIND,
+    F_REPLACE,
+    F_STARTSWITH,
+    F_ENDSWITH,
+    F_UPPER,
+    F_LOWER,
+    F_CHARS,
+    F_REPEAT,
+    F_READLN,
+    F_SQRT,
+    F_SIN,
+    F_COS,
+    F_TAN,
+    F_ATAN2,
+    F_LOG,
+    F_ABS,
+    F_FLOOR,
+    F_CEIL,
+    F_MIN,
+    F_MAX,
+    F_CLAMP,
+    C_PI,
+    C_E,
+    F_READFILE,
+    F_READLINES,
+    F_WRITEFILE,
+    F_APPENDFILE,
+    F_EXISTS,
+    F_LISTDIR,
+    F_DELETEFILE,
+    FILE_RESULT,
+    TEXT_RESULT,
+    LIST_RESULT,
+    F_OK,
+    F_VALUE,
+    F_ERROR,
+    F_EXIT,
+    PROCESS,
+    F_GETENV,
+    F_HASENV,
+    F_SETENV,
+    F_RUN,
+    F_STDOUT,
+    F_STDERR,
+    F_STATUS,
+    THIS,
 ];
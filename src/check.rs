@@ -0,0 +1,193 @@
+use crate::{
+    active_parser::{self, AProgram},
+    errors::{self, codes, source_map, ErrorType, FileLocation, ManagerError, SourceId},
+    lexer, lint, parser,
+};
+use colored::Colorize;
+use std::{fs, path::PathBuf};
+
+const EXTENSION: &str = "tms";
+
+// Type check files without running them. Returns the exit status, which is
+// the status of the earliest stage that failed in any file.
+pub fn check(paths: &[PathBuf]) -> i32 {
+    return check_paths(paths, false);
+}
+
+// Type check files and report lint warnings for them. Warnings give an exit
+// status of their own when nothing failed.
+pub fn lint(paths: &[PathBuf]) -> i32 {
+    return check_paths(paths, true);
+}
+
+fn check_paths(paths: &[PathBuf], lint: bool) -> i32 {
+    let mut status = 0;
+    let mut n_errors = 0;
+    let mut n_warnings = 0;
+
+    let mut report = |msg: String, code: Option<i32>| {
+        match errors::json_messages() {
+            true => println!("{msg}"),
+            false => println!("{msg}\n"),
+        }
+
+        let code = match code {
+            Some(code) => code,
+            None => {
+                n_warnings += 1;
+                return;
+            }
+        };
+        n_errors += 1;
+
+        if status == 0 || code < status {
+            status = code;
+        }
+    };
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            if let Err(err) = collect_files(path, &mut files) {
+                report(err.report(), Some(errors::EXIT_MANAGER));
+            }
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    for file in &files {
+        let (program, source) = match check_file(file) {
+            Ok(ok) => ok,
+            Err((msg, code)) => {
+                report(msg, Some(code));
+                continue;
+            }
+        };
+
+        if !lint {
+            continue;
+        }
+
+        match lint::lint_file(&program, file, source) {
+            Ok(warnings) => {
+                for warning in warnings {
+                    report(warning.report(), None);
+                }
+            }
+            Err(err) => report(err.report(), Some(errors::EXIT_MANAGER)),
+        }
+    }
+
+    let mut summary = format!(
+        "{} {} file{}: {} error{}",
+        if lint { "Linted" } else { "Checked" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        n_errors,
+        if n_errors == 1 { "" } else { "s" }
+    );
+    if lint {
+        summary += &format!(
+            ", {} warning{}",
+            n_warnings,
+            if n_warnings == 1 { "" } else { "s" }
+        );
+    }
+
+    // Keep stdout to one diagnostic per line when writing JSON
+    match (errors::json_messages(), n_errors, n_warnings) {
+        (true, ..) => eprintln!("{summary}"),
+        (false, 0, 0) => println!("{}", summary.green()),
+        (false, 0, _) => println!("{}", summary.yellow()),
+        (false, ..) => println!("{}", summary.red()),
+    }
+
+    if status == 0 && n_warnings > 0 {
+        return errors::EXIT_LINT;
+    }
+
+    return status;
+}
+
+// Find every program file in a directory and its subdirectories
+pub fn collect_files(dir: &PathBuf, files: &mut Vec<PathBuf>) -> Result<(), ManagerError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return Err(ManagerError::new(
+                format!("Could not read directory {} | {err}", dir.display()),
+                FileLocation::None,
+                codes::M0001,
+            ))
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == EXTENSION) {
+            files.push(path);
+        }
+    }
+
+    return Ok(());
+}
+
+fn check_file(file: &PathBuf) -> Result<(AProgram, SourceId), (String, i32)> {
+    let mut program = match fs::read_to_string(file) {
+        Ok(program) => program,
+        Err(err) => {
+            let err = ManagerError::new(
+                format!("Could not open program file {} | {err}", file.display()),
+                FileLocation::None,
+                codes::M0001,
+            );
+            return Err((err.report(), errors::EXIT_MANAGER));
+        }
+    };
+    program.push(' ');
+    let source = source_map::add_source(file.clone(), program.clone());
+
+    let lex_out = match lexer::lex(&program, false, &source, "", &[]) {
+        Ok(lex) => lex,
+        Err(mut err) => {
+            name_file(&mut err.0, &err.1, file);
+            return Err((err.report(), errors::EXIT_LEXER));
+        }
+    };
+
+    let parse_out = match parser::parse(lex_out, &source) {
+        Ok(parse) => parse,
+        Err(ErrorType::Parser(mut err)) => {
+            name_file(&mut err.0, &err.1, file);
+            return Err((err.report(), errors::EXIT_PARSER));
+        }
+        Err(ErrorType::Lexer(mut err)) => {
+            name_file(&mut err.0, &err.1, file);
+            return Err((err.report(), errors::EXIT_LEXER));
+        }
+        Err(_) => panic!(),
+    };
+
+    match active_parser::aparse(&parse_out) {
+        Ok(program) => return Ok((program, source)),
+        Err(mut err) => {
+            name_file(&mut err.0, &err.1, file);
+            return Err((err.report(), errors::EXIT_APARSER));
+        }
+    }
+}
+
+// Errors without a location would not otherwise say which file they are from
+fn name_file(msg: &mut String, loc: &FileLocation, file: &PathBuf) {
+    if *loc == FileLocation::None {
+        *msg = format!("{}: {msg}", file.display());
+    }
+}
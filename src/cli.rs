@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand};
+use crate::{
+    finterpretor::sandbox::Sandbox,
+    lsp::{Position, Range},
+};
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -10,6 +14,39 @@ use std::path::PathBuf;
 pub struct Args {
     #[command(subcommand)]
     pub cmd: Command,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = MessageFormat::Human,
+        help = "How errors are printed."
+    )]
+    pub message_format: MessageFormat,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ColorChoice::Auto,
+        help = "When to color output. Auto colors a terminal unless NO_COLOR is set."
+    )]
+    pub color: ColorChoice,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+pub enum MessageFormat {
+    #[value(help = "Colored text with the source of the error.")]
+    Human,
+    #[value(help = "One JSON object per error, each on its own line.")]
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
 }
 
 #[derive(Subcommand)]
@@ -21,6 +58,9 @@ pub enum Command {
 
         #[arg(help = "Command line arguments.")]
         args: Vec<String>,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
     },
 
     #[command(about = "Run a program.")]
@@ -30,15 +70,81 @@ pub enum Command {
 
         #[arg(help = "Command line arguments")]
         args: Vec<String>,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
     },
 
-    #[command(about = "Format a file.")]
-    Format {
-        #[arg(help = "File with valid syntax")]
+    #[command(about = "Start an interactive session.")]
+    Repl {
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+
+    #[command(about = "Check programs for errors without running them.")]
+    Check {
+        #[arg(
+            required = true,
+            help = "Files, or directories to search for .tms files."
+        )]
+        paths: Vec<PathBuf>,
+    },
+
+    #[command(about = "Check programs and warn about likely mistakes.")]
+    Lint {
+        #[arg(
+            required = true,
+            help = "Files, or directories to search for .tms files."
+        )]
+        paths: Vec<PathBuf>,
+    },
+
+    #[command(about = "Explain an error code.")]
+    Explain {
+        #[arg(help = "Error code, such as T0003. Lists every code if left out.")]
+        code: Option<String>,
+    },
+
+    #[command(about = "Show the output of a compiler stage.")]
+    Dump {
+        #[arg(long, value_enum, help = "Stage to show the output of.")]
+        stage: Stage,
+
+        #[arg(help = "File containing entry function.")]
         file: PathBuf,
+    },
+
+    #[command(about = "Format programs.")]
+    Format {
+        #[arg(
+            help = "Files, or directories to search for .tms files. Formats standard input to standard output if left out."
+        )]
+        paths: Vec<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Print a diff for each file that is not formatted instead of rewriting it."
+        )]
+        check: bool,
 
-        #[arg(help = "Whether to send formats to stdout.")]
-        to_stdout: Option<bool>,
+        #[arg(
+            long,
+            conflicts_with = "check",
+            help = "Print formatted files instead of rewriting them."
+        )]
+        stdout: bool,
+    },
+
+    #[command(about = "Rename a struct, field, function or variable everywhere it is used.")]
+    Rename {
+        #[arg(
+            value_parser = parse_target,
+            help = "Name to rename as file:line:col, counted from one."
+        )]
+        target: Target,
+
+        #[arg(help = "New name.")]
+        new_name: String,
     },
 
     #[command(about = "Update TermsLang.")]
@@ -58,4 +164,194 @@ pub enum Command {
         #[arg(help = "Whether to run parsing.")]
         run_parse: Option<bool>,
     },
+
+    #[command(about = "Answer a language server request for a document as JSON.")]
+    LspRequest {
+        #[command(subcommand)]
+        request: LspRequest,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LspRequest {
+    #[command(about = "Edits that format the document.")]
+    Formatting {
+        #[command(flatten)]
+        doc: DocumentArgs,
+    },
+
+    #[command(about = "Edits that format the lines of a range of the document.")]
+    RangeFormatting {
+        #[command(flatten)]
+        doc: DocumentArgs,
+
+        #[arg(
+            value_parser = parse_range,
+            help = "Range to format as line:character-line:character, counted from zero."
+        )]
+        range: Range,
+    },
+
+    #[command(about = "Kinds of the names in the document, for highlighting.")]
+    SemanticTokens {
+        #[command(flatten)]
+        doc: DocumentArgs,
+    },
+
+    #[command(about = "Token types and modifiers that semantic tokens refer to.")]
+    SemanticTokensLegend,
+
+    #[command(about = "Outline of the structs and functions in the document.")]
+    DocumentSymbol {
+        #[command(flatten)]
+        doc: DocumentArgs,
+    },
+
+    #[command(about = "Edits that rename the name at a position everywhere it is used.")]
+    Rename {
+        #[command(flatten)]
+        doc: DocumentArgs,
+
+        #[arg(
+            value_parser = parse_position,
+            help = "Position of the name as line:character, counted from zero."
+        )]
+        position: Position,
+
+        #[arg(help = "New name.")]
+        new_name: String,
+    },
+
+    #[command(about = "Parameters of the call around a position.")]
+    SignatureHelp {
+        #[command(flatten)]
+        doc: DocumentArgs,
+
+        #[arg(
+            value_parser = parse_position,
+            help = "Position in the call as line:character, counted from zero."
+        )]
+        position: Position,
+    },
+
+    #[command(about = "Parameter names at calls and the types of loop counters.")]
+    InlayHint {
+        #[command(flatten)]
+        doc: DocumentArgs,
+
+        #[arg(
+            value_parser = parse_range,
+            help = "Range to give hints for as line:character-line:character, counted from zero."
+        )]
+        range: Range,
+    },
+}
+
+fn parse_position(text: &str) -> Result<Position, String> {
+    match Position::parse(text) {
+        Some(position) => return Ok(position),
+        None => return Err(String::from("expected line:character")),
+    }
+}
+
+#[derive(Clone)]
+pub struct Target {
+    pub file: PathBuf,
+    pub line: usize,
+    pub col: usize,
+}
+
+fn parse_target(text: &str) -> Result<Target, String> {
+    let mut parts = text.rsplitn(3, ':');
+    let (col, line, file) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(col), Some(line), Some(file)) => (col, line, file),
+        _ => return Err(String::from("expected file:line:col")),
+    };
+
+    match (line.parse::<usize>(), col.parse::<usize>()) {
+        (Ok(line), Ok(col)) if line > 0 && col > 0 => {
+            return Ok(Target {
+                file: PathBuf::from(file),
+                line,
+                col,
+            })
+        }
+        _ => return Err(String::from("expected a line and column counted from one")),
+    }
+}
+
+fn parse_range(text: &str) -> Result<Range, String> {
+    match Range::parse(text) {
+        Some(range) => return Ok(range),
+        None => return Err(String::from("expected line:character-line:character")),
+    }
+}
+
+#[derive(ClapArgs)]
+pub struct DocumentArgs {
+    #[arg(help = "TermsLang File.")]
+    pub file: PathBuf,
+
+    #[arg(
+        long,
+        help = "Read the document from standard input instead of the file."
+    )]
+    pub stdin: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+pub enum Stage {
+    #[value(help = "Tokens from the lexer.")]
+    Tokens,
+    #[value(help = "Syntax tree from the parser.")]
+    Ast,
+    #[value(help = "Typed syntax tree from the active parser.")]
+    Aast,
+    #[value(help = "Flat instructions run by the interpreter.")]
+    Ir,
+}
+
+#[derive(ClapArgs)]
+pub struct SandboxArgs {
+    #[arg(
+        long,
+        require_equals = true,
+        num_args = 0..,
+        value_delimiter = ',',
+        help = "Allow reading files. Limited to the given paths if any are listed."
+    )]
+    pub allow_read: Option<Vec<PathBuf>>,
+
+    #[arg(
+        long,
+        require_equals = true,
+        num_args = 0..,
+        value_delimiter = ',',
+        help = "Allow writing and deleting files. Limited to the given paths if any are listed."
+    )]
+    pub allow_write: Option<Vec<PathBuf>>,
+
+    #[arg(long, help = "Allow getting and setting environment variables.")]
+    pub allow_env: bool,
+
+    #[arg(long, help = "Allow running other programs.")]
+    pub allow_run: bool,
+
+    #[arg(long, help = "Stop the program after this many commands.")]
+    pub max_steps: Option<u64>,
+
+    #[arg(long, help = "Stop the program once it holds this many bytes.")]
+    pub max_memory: Option<usize>,
+}
+impl SandboxArgs {
+    pub fn to_sandbox(&self) -> Sandbox {
+        Sandbox::new(
+            self.allow_read.clone(),
+            self.allow_write.clone(),
+            self.allow_env,
+            self.allow_run,
+            self.max_steps,
+            self.max_memory,
+        )
+    }
 }
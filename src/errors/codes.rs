@@ -0,0 +1,1031 @@
+// Stable codes for each kind of error. The letter gives the stage that
+// reports it: M manager, L lexer, P parser, T active parser (types),
+// R runtime and S language server. W codes are lint warnings.
+pub type ErrorCode = &'static str;
+
+pub const M0001: ErrorCode = "M0001";
+pub const M0002: ErrorCode = "M0002";
+pub const M0003: ErrorCode = "M0003";
+pub const M0004: ErrorCode = "M0004";
+pub const M0005: ErrorCode = "M0005";
+pub const M0006: ErrorCode = "M0006";
+pub const M0007: ErrorCode = "M0007";
+
+pub const L0001: ErrorCode = "L0001";
+pub const L0002: ErrorCode = "L0002";
+pub const L0003: ErrorCode = "L0003";
+
+pub const P0001: ErrorCode = "P0001";
+pub const P0002: ErrorCode = "P0002";
+pub const P0003: ErrorCode = "P0003";
+pub const P0004: ErrorCode = "P0004";
+pub const P0005: ErrorCode = "P0005";
+pub const P0006: ErrorCode = "P0006";
+pub const P0007: ErrorCode = "P0007";
+pub const P0008: ErrorCode = "P0008";
+pub const P0009: ErrorCode = "P0009";
+pub const P0010: ErrorCode = "P0010";
+pub const P0011: ErrorCode = "P0011";
+pub const P0012: ErrorCode = "P0012";
+pub const P0013: ErrorCode = "P0013";
+pub const P0014: ErrorCode = "P0014";
+pub const P0015: ErrorCode = "P0015";
+
+pub const T0001: ErrorCode = "T0001";
+pub const T0002: ErrorCode = "T0002";
+pub const T0003: ErrorCode = "T0003";
+pub const T0004: ErrorCode = "T0004";
+pub const T0005: ErrorCode = "T0005";
+pub const T0006: ErrorCode = "T0006";
+pub const T0007: ErrorCode = "T0007";
+pub const T0008: ErrorCode = "T0008";
+pub const T0009: ErrorCode = "T0009";
+pub const T0010: ErrorCode = "T0010";
+pub const T0011: ErrorCode = "T0011";
+pub const T0012: ErrorCode = "T0012";
+pub const T0013: ErrorCode = "T0013";
+pub const T0014: ErrorCode = "T0014";
+pub const T0015: ErrorCode = "T0015";
+pub const T0016: ErrorCode = "T0016";
+pub const T0017: ErrorCode = "T0017";
+pub const T0018: ErrorCode = "T0018";
+
+pub const R0001: ErrorCode = "R0001";
+pub const R0002: ErrorCode = "R0002";
+pub const R0003: ErrorCode = "R0003";
+pub const R0004: ErrorCode = "R0004";
+pub const R0005: ErrorCode = "R0005";
+pub const R0006: ErrorCode = "R0006";
+pub const R0007: ErrorCode = "R0007";
+pub const R0008: ErrorCode = "R0008";
+pub const R0009: ErrorCode = "R0009";
+
+pub const S0001: ErrorCode = "S0001";
+pub const S0002: ErrorCode = "S0002";
+pub const S0003: ErrorCode = "S0003";
+pub const S0004: ErrorCode = "S0004";
+pub const S0005: ErrorCode = "S0005";
+
+pub const W0001: ErrorCode = "W0001";
+pub const W0002: ErrorCode = "W0002";
+pub const W0003: ErrorCode = "W0003";
+pub const W0004: ErrorCode = "W0004";
+pub const W0005: ErrorCode = "W0005";
+pub const W0006: ErrorCode = "W0006";
+pub const W0007: ErrorCode = "W0007";
+pub const W0008: ErrorCode = "W0008";
+
+// Longer description of an error code, shown by `termslang explain`
+pub struct Explanation {
+    pub code: ErrorCode,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub wrong: &'static str,
+    pub right: &'static str,
+}
+
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    let code = code.to_uppercase();
+    return EXPLANATIONS.iter().find(|e| e.code == code);
+}
+
+pub const EXPLANATIONS: [Explanation; 65] = [
+    Explanation {
+        code: M0001,
+        title: "Could not open a program file",
+        description: "A file or directory given on the command line could not be read. \
+            Check that the path is spelled correctly and that it can be read.",
+        wrong: "termslang run mian.tms",
+        right: "termslang run main.tms",
+    },
+    Explanation {
+        code: M0002,
+        title: "Could not write a program file",
+        description: "`termslang format` writes the formatted program back to its file, \
+            which failed. Check that the file can be written to.",
+        wrong: "chmod u-w main.tms\ntermslang format main.tms",
+        right: "chmod u+w main.tms\ntermslang format main.tms",
+    },
+    Explanation {
+        code: M0003,
+        title: "Update failed",
+        description: "`termslang update` installs the latest version with cargo, which \
+            could not be started. Install Rust and make sure cargo is on your PATH.",
+        wrong: "PATH=/usr/bin termslang update",
+        right: "PATH=\"$HOME/.cargo/bin:$PATH\" termslang update",
+    },
+    Explanation {
+        code: M0004,
+        title: "Unknown REPL command",
+        description: "Lines starting with `:` in `termslang repl` are commands. The \
+            commands are `:type <expr>`, `:load <file>`, `:reset` and `:quit`.",
+        wrong: ">> :typ 1 + 2",
+        right: ">> :type 1 + 2",
+    },
+    Explanation {
+        code: M0005,
+        title: "Unknown error code",
+        description: "`termslang explain` was given a code that does not exist. Run it \
+            without a code to list every code.",
+        wrong: "termslang explain X0001",
+        right: "termslang explain T0003",
+    },
+    Explanation {
+        code: M0006,
+        title: "Invalid lint setting",
+        description: "A `# lint:` comment or a line of a `.termslint` file could not be \
+            read. Each setting is `allow` or `warn` followed by rule names, and \
+            `termslang explain` lists the rules under the W codes.",
+        wrong: r#"# lint: allow unused_var
+"Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+        right: r#"# lint: allow unused_variable
+"Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+    },
+    Explanation {
+        code: M0007,
+        title: "Invalid format setting",
+        description: "A line of a `termsfmt.toml` file could not be read. The settings \
+            are `indent_width` and `max_width`, which are positive whole numbers, and \
+            `brace_style`, which is \"same_line\" or \"next_line\".",
+        wrong: "indent_width = \"four\"",
+        right: "indent_width = 4",
+    },
+    Explanation {
+        code: L0001,
+        title: "Invalid operator",
+        description: "The program contains a symbol that is not an operator of the language.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int x = 5 ? 2 ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int x = 5 + 2 ~
+}"#,
+    },
+    Explanation {
+        code: L0002,
+        title: "Character not allowed inside a name",
+        description: "`@` may only start a name, as in `@main` or `@str`. It can not be \
+            used inside a name.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int my@count = 1 ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int myCount = 1 ~
+}"#,
+    },
+    Explanation {
+        code: L0003,
+        title: "Unterminated string",
+        description: "A string was opened but the file ended before its closing quote. \
+            Strings can span lines, so the missing quote may be far above the end of the file.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println "Hello ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    println "Hello" ~
+}"#,
+    },
+    Explanation {
+        code: P0001,
+        title: "Program file is empty",
+        description: "There is nothing in the program file to run.",
+        wrong: "",
+        right: r#""Example"
+func null @main: str[] args {
+    println "Hello World" ~
+}"#,
+    },
+    Explanation {
+        code: P0002,
+        title: "Missing program prelude string",
+        description: "Every program file starts with a string describing the program.",
+        wrong: r#"func null @main: str[] args {
+    println "Hello World" ~
+}"#,
+        right: r#""Hello World Program"
+func null @main: str[] args {
+    println "Hello World" ~
+}"#,
+    },
+    Explanation {
+        code: P0003,
+        title: "Expected line terminator",
+        description: "Every term ends with `~`. Inside a function a missing `~` is \
+            usually reported as P0008, as the expression runs on into the next line.",
+        wrong: r#""Example"
+import fib of "fib.tms"
+func null @main: str[] args {
+}"#,
+        right: r#""Example"
+import fib of "fib.tms" ~
+func null @main: str[] args {
+}"#,
+    },
+    Explanation {
+        code: P0004,
+        title: "Unexpected token",
+        description: "A token was found where the language does not allow it. Function \
+            arguments, for example, are separated by commas.",
+        wrong: r#""Example"
+func int add: int a int b {
+    return a + b ~
+}"#,
+        right: r#""Example"
+func int add: int a, int b {
+    return a + b ~
+}"#,
+    },
+    Explanation {
+        code: P0005,
+        title: "Unexpected end of file",
+        description: "The file ended in the middle of a function, struct or loop \
+            definition.",
+        wrong: r#""Example"
+func null greet"#,
+        right: r#""Example"
+func null greet {
+}"#,
+    },
+    Explanation {
+        code: P0006,
+        title: "Expected a name",
+        description: "Variables, arguments, loop counters, functions and structs all need \
+            a name.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int = 5 ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+    },
+    Explanation {
+        code: P0007,
+        title: "Expected a block",
+        description: "Functions, structs, `if`, `else` and `loop` are followed by a block \
+            in braces, and every block is closed.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println "Hello World" ~
+"#,
+        right: r#""Example"
+func null @main: str[] args {
+    println "Hello World" ~
+}"#,
+    },
+    Explanation {
+        code: P0008,
+        title: "Invalid expression",
+        description: "An expression is incomplete, such as an operator missing a value on \
+            one side or a bracket left open, or is followed by something that is not part \
+            of it.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int x = 1 + ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int x = 1 + 2 ~
+}"#,
+    },
+    Explanation {
+        code: P0009,
+        title: "Invalid use of dot",
+        description: "A dot is followed by a single field or method name. A literal needs \
+            parentheses around it before a dot.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println "hello".@upper.() ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    println ("hello").@upper.() ~
+}"#,
+    },
+    Explanation {
+        code: P0010,
+        title: "Invalid import",
+        description: "Imports name the object to import and the file to import it from.",
+        wrong: r#""Example"
+import fib "fib.tms" ~"#,
+        right: r#""Example"
+import fib of "fib.tms" ~"#,
+    },
+    Explanation {
+        code: P0011,
+        title: "Expected set operator",
+        description: "`updt` needs a set operator such as `=` or `+=` between the variable \
+            and its new value.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int x = 5 ~
+    updt x 6 ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int x = 5 ~
+    updt x = 6 ~
+}"#,
+    },
+    Explanation {
+        code: P0012,
+        title: "Invalid term at the top level",
+        description: "Only `struct`, `func` and `import` may appear outside of a function.",
+        wrong: r#""Example"
+let int count = 5 ~
+func null @main: str[] args {
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+    },
+    Explanation {
+        code: P0013,
+        title: "Invalid REPL entry",
+        description: "`termslang repl` runs terms as if they were inside a function, but \
+            there is no function to return from. Imports are done with `:load`.",
+        wrong: ">> return 5",
+        right: ">> 5",
+    },
+    Explanation {
+        code: P0014,
+        title: "Invalid creation or type syntax",
+        description: "`$` is followed by its arguments in parentheses, even when there are \
+            none, and the brackets of an array type are closed.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = $ int[] ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int[] a = $() int[] ~
+}"#,
+    },
+    Explanation {
+        code: P0015,
+        title: "Struct field with a value",
+        description: "Struct fields are declared without a value. Set them in `@new` \
+            instead.",
+        wrong: r#""Example"
+struct Counter {
+    let int count = 0 ~
+}"#,
+        right: r#""Example"
+struct Counter {
+    let int count ~
+
+    func null @new {
+        updt @this.count = 0 ~
+    }
+}"#,
+    },
+    Explanation {
+        code: T0001,
+        title: "Unknown name",
+        description: "No variable, function or struct with this name is in scope.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println mesage ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let str message = "Hello" ~
+    println message ~
+}"#,
+    },
+    Explanation {
+        code: T0002,
+        title: "Duplicate definition",
+        description: "Two structs or functions were given the same name.",
+        wrong: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func int twice: int n {
+    return n + n ~
+}"#,
+        right: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}"#,
+    },
+    Explanation {
+        code: T0003,
+        title: "Mismatched types",
+        description: "A value does not have the type of the variable it is stored in.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int count = "five" ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+    },
+    Explanation {
+        code: T0004,
+        title: "Mismatched argument type",
+        description: "An argument does not have the type the function takes.",
+        wrong: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    let int x = twice.("2") ~
+}"#,
+        right: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    let int x = twice.(2) ~
+}"#,
+    },
+    Explanation {
+        code: T0005,
+        title: "Expected a bool",
+        description: "Conditions of `if` and `loop`, and the operands of `&&`, `||` and \
+            `!`, must be of type bool.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    if 1 {
+        println "yes" ~
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    if 1 == 1 {
+        println "yes" ~
+    }
+}"#,
+    },
+    Explanation {
+        code: T0006,
+        title: "Printing a value that is not a string",
+        description: "`print` and `println` take a str. Convert other values with `@str`.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println 5 ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    println (5).@str.() ~
+}"#,
+    },
+    Explanation {
+        code: T0007,
+        title: "Mismatched array element type",
+        description: "Every element of an array has the same type, and array sizes are \
+            of type int.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = [1, "two"] ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int[] a = [1, 2] ~
+}"#,
+    },
+    Explanation {
+        code: T0008,
+        title: "Wrong number of arguments",
+        description: "A function, method or `$()` was given more or fewer arguments than \
+            it takes.",
+        wrong: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    let int x = twice.(1, 2) ~
+}"#,
+        right: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    let int x = twice.(1) ~
+}"#,
+    },
+    Explanation {
+        code: T0009,
+        title: "Mismatched return type",
+        description: "A function returns a value of a different type than it declares, or \
+            does not return on every path.",
+        wrong: r#""Example"
+func int answer {
+    return "42" ~
+}"#,
+        right: r#""Example"
+func int answer {
+    return 42 ~
+}"#,
+    },
+    Explanation {
+        code: T0010,
+        title: "Code after return",
+        description: "`return` must be the last term in its block.",
+        wrong: r#""Example"
+func int answer {
+    return 42 ~
+    println "done" ~
+}"#,
+        right: r#""Example"
+func int answer {
+    println "done" ~
+    return 42 ~
+}"#,
+    },
+    Explanation {
+        code: T0011,
+        title: "Unknown field or method",
+        description: "The struct or type has no field or method with this name.",
+        wrong: r#""Example"
+struct Point {
+    let int x ~
+}
+func null @main: str[] args {
+    let Point p = $() Point ~
+    let int y = p.y ~
+}"#,
+        right: r#""Example"
+struct Point {
+    let int x ~
+}
+func null @main: str[] args {
+    let Point p = $() Point ~
+    let int x = p.x ~
+}"#,
+    },
+    Explanation {
+        code: T0012,
+        title: "Type used as a value",
+        description: "A type, struct or function definition was used where a value is \
+            needed. Use a value of the type, create a struct with `$()` or call the \
+            function.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int x = int ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int x = 0 ~
+}"#,
+    },
+    Explanation {
+        code: T0013,
+        title: "Invalid assignment",
+        description: "Only variables, fields and array elements can be updated.",
+        wrong: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    updt twice = 1 ~
+}"#,
+        right: r#""Example"
+func int twice: int n {
+    return n * 2 ~
+}
+func null @main: str[] args {
+    let int x = twice.(1) ~
+    updt x = 1 ~
+}"#,
+    },
+    Explanation {
+        code: T0014,
+        title: "Break or continue outside a loop",
+        description: "`break` and `continue` can only be used inside a `loop`.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    break ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    loop i: i < 10 {
+        break ~
+    }
+}"#,
+    },
+    Explanation {
+        code: T0015,
+        title: "Empty array literal",
+        description: "The element type of `[]` can not be inferred. Create an empty array \
+            with `$()` instead.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = [] ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int[] a = $() int[] ~
+}"#,
+    },
+    Explanation {
+        code: T0016,
+        title: "Type can not be created",
+        description: "Some built-in types, like `process`, are only returned by built-in \
+            functions and can not be created with `$()`.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let process p = $() process ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let process p = @run.("ls", $() str[]) ~
+}"#,
+    },
+    Explanation {
+        code: T0017,
+        title: "Unsupported array element type",
+        description: "`@sort` needs elements of type int, float or str, and `@join` needs \
+            elements of type str.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = [3, 1, 2] ~
+    println a.@join.(", ") ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let str[] a = ["3", "1", "2"] ~
+    println a.@join.(", ") ~
+}"#,
+    },
+    Explanation {
+        code: T0018,
+        title: "Built-in struct name reused",
+        description: "A struct was given the name of a built-in struct, such as `str`, \
+            `process` or `fileresult`.",
+        wrong: r#""Example"
+struct process {
+    let str name ~
+}
+func null @main: str[] args {
+}"#,
+        right: r#""Example"
+struct task {
+    let str name ~
+}
+func null @main: str[] args {
+}"#,
+    },
+    Explanation {
+        code: R0001,
+        title: "Index out of range",
+        description: "An index, slice or substring is outside of the array or string.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = [1, 2, 3] ~
+    println a[3].@str.() ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int[] a = [1, 2, 3] ~
+    println a[2].@str.() ~
+}"#,
+    },
+    Explanation {
+        code: R0002,
+        title: "Invalid number conversion",
+        description: "A string passed to `@int` or `@float` is not a number.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int x = ("five").@int.() ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int x = ("5").@int.() ~
+}"#,
+    },
+    Explanation {
+        code: R0003,
+        title: "Invalid math operation",
+        description: "A math operation has no finite result, or its result does not fit in \
+            an int.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let float x = @sqrt.(-1.0) ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let float x = @sqrt.(1.0) ~
+}"#,
+    },
+    Explanation {
+        code: R0004,
+        title: "Invalid argument",
+        description: "A built-in function was given an argument it can not use, such as a \
+            negative count or size.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    println ("ab").@repeat.(-1) ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    println ("ab").@repeat.(2) ~
+}"#,
+    },
+    Explanation {
+        code: R0005,
+        title: "Not allowed by the sandbox",
+        description: "Programs can only use files, environment variables and other \
+            programs when run with `--allow-read`, `--allow-write`, `--allow-env` or \
+            `--allow-run`.",
+        wrong: "termslang run notes.tms",
+        right: "termslang run notes.tms --allow-read=notes.txt",
+    },
+    Explanation {
+        code: R0006,
+        title: "Input or output failed",
+        description: "Reading input or starting another program failed. The message \
+            includes the reason given by the system. Running the examples needs `--allow-run`.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let process p = @run.("no-such-program", $() str[]) ~
+    print p.@stdout ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let process p = @run.("echo", ["hello"]) ~
+    print p.@stdout ~
+}"#,
+    },
+    Explanation {
+        code: R0007,
+        title: "Step limit exceeded",
+        description: "The program ran more commands than `--max-steps` allows.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    loop i: true {
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    loop i: i < 10 {
+    }
+}"#,
+    },
+    Explanation {
+        code: R0008,
+        title: "Memory limit exceeded",
+        description: "The program held more memory than `--max-memory` allows. Heap values \
+            and strings count towards the limit, which is checked before large arrays \
+            and strings are made.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int[] a = $() int[] ~
+    loop i: true {
+        cll a.@append.(i) ~
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int[] a = $() int[] ~
+    loop i: i < 10 {
+        cll a.@append.(i) ~
+    }
+}"#,
+    },
+    Explanation {
+        code: R0009,
+        title: "Exit status out of range",
+        description: "`@exit` was called with, or `@main` returned, a status outside of \
+            [0, 255]. The OS keeps only the lowest 8 bits, so such a status could look \
+            like success.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    cll @exit.(256) ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    cll @exit.(1) ~
+}"#,
+    },
+    Explanation {
+        code: S0001,
+        title: "Variable outside of scope",
+        description: "The language server found a variable declared outside of any \
+            function or struct.",
+        wrong: r#""Example"
+let int count = 5 ~"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+}"#,
+    },
+    Explanation {
+        code: S0002,
+        title: "Invalid new name",
+        description: "A rename was given a new name that can not be used. Names start \
+            with a letter, `_` or `@` and go on with letters, digits and `_`. Keywords \
+            and built-in names such as `int`, `@str` or `@this` are taken.",
+        wrong: "termslang rename main.tms:4:9 loop",
+        right: "termslang rename main.tms:4:9 count",
+    },
+    Explanation {
+        code: S0003,
+        title: "Rename conflict",
+        description: "The new name is already used where the renamed name is seen, or \
+            the renamed name would hide another use of the new name. The rename would \
+            change what the program means, so nothing was changed. The note points to \
+            what the new name already refers to.",
+        wrong: r#"# termslang rename main.tms:5:13 total
+"Example"
+func null @main: str[] args {
+    let int total = 0 ~
+    let int count = 5 ~
+    updt total += count ~
+}"#,
+        right: r#"# termslang rename main.tms:5:13 amount
+"Example"
+func null @main: str[] args {
+    let int total = 0 ~
+    let int count = 5 ~
+    updt total += count ~
+}"#,
+    },
+    Explanation {
+        code: S0004,
+        title: "Nothing to rename",
+        description: "A rename must start on a name declared in a program: a struct, \
+            field, function, method, parameter, variable or loop counter. Built-in \
+            names, `@main` and `@new` can not be renamed.",
+        wrong: "termslang rename main.tms:3:27 text",
+        right: "termslang rename main.tms:3:13 text",
+    },
+    Explanation {
+        code: S0005,
+        title: "File using the name has errors",
+        description: "A struct or function is renamed in every file in the directory \
+            that uses it. One of them has errors, so the names it uses could not be \
+            found. Fix the errors, or check the file, then rename again.",
+        wrong: "termslang rename lib.tms:3:10 total",
+        right: "termslang check .\ntermslang rename lib.tms:3:10 total",
+    },
+    Explanation {
+        code: W0001,
+        title: "Unused variable (unused_variable)",
+        description: "A variable is declared but its value is never read. Remove it, \
+            or start its name with `_` if it is kept on purpose.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+    println "done" ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+    println count.@str.() ~
+}"#,
+    },
+    Explanation {
+        code: W0002,
+        title: "Unused function (unused_function)",
+        description: "A function is never called or referred to.",
+        wrong: r#""Example"
+func int double: int x {
+    return x * 2 ~
+}
+
+func null @main: str[] args {
+    println "done" ~
+}"#,
+        right: r#""Example"
+func int double: int x {
+    return x * 2 ~
+}
+
+func null @main: str[] args {
+    println double.(2).@str.() ~
+}"#,
+    },
+    Explanation {
+        code: W0003,
+        title: "Unused import (unused_import)",
+        description: "An imported object is never used in the file that imports it.",
+        wrong: r#""Example"
+import fib of "fib.tms" ~
+
+func null @main: str[] args {
+    println "done" ~
+}"#,
+        right: r#""Example"
+import fib of "fib.tms" ~
+
+func null @main: str[] args {
+    println fib.(10).@str.() ~
+}"#,
+    },
+    Explanation {
+        code: W0004,
+        title: "Shadowed variable (shadowing)",
+        description: "A variable is declared with the same name as one already in \
+            scope, hiding the first one for the rest of the block.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    loop idx: idx < 3 {
+        let int idx = idx + 1 ~
+        println idx.@str.() ~
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    loop idx: idx < 3 {
+        let int n = idx + 1 ~
+        println n.@str.() ~
+    }
+}"#,
+    },
+    Explanation {
+        code: W0005,
+        title: "Unreachable code (unreachable)",
+        description: "Terms after `break` or `continue` in the same block are never run.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    loop i: i < 10 {
+        break ~
+        println i.@str.() ~
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    loop i: i < 10 {
+        if i == 5 {
+            break ~
+        }
+        println i.@str.() ~
+    }
+}"#,
+    },
+    Explanation {
+        code: W0006,
+        title: "Variable assigned to itself (self_assignment)",
+        description: "An `updt` sets a variable to its own value, which does nothing.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+    updt count = count ~
+    println count.@str.() ~
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    let int count = 5 ~
+    updt count = count + 1 ~
+    println count.@str.() ~
+}"#,
+    },
+    Explanation {
+        code: W0007,
+        title: "Constant condition (constant_condition)",
+        description: "The condition of an `if` or `loop` is made only of literals, so it \
+            is the same every time. A `loop` on `true` is allowed, as it is the way to \
+            loop until a `break`.",
+        wrong: r#""Example"
+func null @main: str[] args {
+    if 1 > 2 {
+        println "never" ~
+    }
+}"#,
+        right: r#""Example"
+func null @main: str[] args {
+    if args.@len.() > 2 {
+        println "many" ~
+    }
+}"#,
+    },
+    Explanation {
+        code: W0008,
+        title: "Unused return value (unused_return)",
+        description: "A `cll` calls something that returns a value, which is then \
+            thrown away.",
+        wrong: r#""Example"
+func int double: int x {
+    return x * 2 ~
+}
+
+func null @main: str[] args {
+    cll double.(2) ~
+}"#,
+        right: r#""Example"
+func int double: int x {
+    return x * 2 ~
+}
+
+func null @main: str[] args {
+    println double.(2).@str.() ~
+}"#,
+    },
+];
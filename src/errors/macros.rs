@@ -1,121 +1,52 @@
 macro_rules! prettify_macro {
-    ($text:expr) => {
+    ($text:expr, $stage:expr) => {
+        prettify_macro! {$text, $stage, "error", Color::Red}
+    };
+    ($text:expr, $stage:expr, $severity:expr, $color:expr) => {
+        // Convert to an error message in the format chosen on the command line
+        pub fn report(&self) -> String {
+            return match json_messages() {
+                true => self.json(),
+                false => self.prettify(),
+            };
+        }
+
         // Convert to pretty Error
         pub fn prettify(&self) -> String {
-            let file_location: &FileLocation = &self.1;
-            let err_msg: &String = &self.0;
-            let t: &str = $text;
-
-            // Check to see if error has file position attached
-            match file_location {
-                // File position attached
-                FileLocation::Loc {
-                    file,
-                    start_line,
-                    end_line,
-                    start_col,
-                    end_col,
-                } => {
-                    let program = fs::read_to_string(file).unwrap();
-
-                    // Create initial message
-                    let mut msg = format!(
-                        "{} {}:{}:{} ({})\n",
-                        t.red(),
-                        file.display(),
-                        start_line + 1,
-                        start_col + 1,
-                        err_msg
-                    );
-
-                    // Get all the lines the error occurred on
-                    let offset_start = if *start_line > 0 { 1 } else { 0 };
-                    let line_range = (start_line - offset_start, end_line + 1);
-                    let lines = &program.lines().collect::<Vec<&str>>()[line_range.0..line_range.1];
-                    let lines = lines.into_iter().map(|x| x.to_string());
-
-                    // Loop over lines and add them to message
-                    for (line_idx, line) in lines.enumerate() {
-                        let line_num = line_idx + start_line - offset_start;
-                        msg += &format!("\n{: >5}|", line_num + 1);
+            let title = format!("{}[{}]", $text, self.2);
+            let mut msg = render(title.color($color), &self.0, &self.1, $color);
 
-                        if line_num == *start_line {
-                            let start_col = if *start_col == 0 { 0 } else { start_col - 1 };
-
-                            let good1 = &line[..start_col];
-                            let (bad, good2) = if end_line == start_line {
-                                let bad = &line[start_col..*end_col];
-                                let good2 = &line[*end_col..];
+            // Add notes pointing at related locations
+            for note in &self.3 {
+                msg += "\n\n";
+                msg += &render("Note".blue(), &note.0, &note.1, Color::Blue);
+            }
 
-                                (bad, good2)
-                            } else {
-                                let bad = &line[start_col..];
-                                let good2 = "";
+            return msg;
+        }
 
-                                (bad, good2)
-                            };
+        pub fn json(&self) -> String {
+            let mut json = format!(
+                "{{\"severity\":\"{}\",\"stage\":\"{}\",\"code\":\"{}\",",
+                $severity, $stage, self.2
+            );
 
-                            msg += &format!("{}{}{}", good1.green(), bad.red(), good2.green());
-                        } else if line_num == *end_line {
-                            let bad = &line[..*end_col];
-                            let good = &line[*end_col..];
-                            msg += &format!("{}{}", bad.red(), good.green())
-                        } else if line_num > *start_line && line_num < *end_line {
-                            msg += &format!("{}", line.red())
-                        } else {
-                            msg += &format!("{}", line.green())
-                        }
-                    }
+            json += &json_location(&self.1);
+            json += &format!("\"msg\":{},\"notes\":[", json_string(&self.0));
 
-                    // Return pretty message
-                    return msg;
+            for (idx, note) in self.3.iter().enumerate() {
+                if idx > 0 {
+                    json += ",";
                 }
-                // End of file
-                FileLocation::End { file } => {
-                    let program = fs::read_to_string(file).unwrap();
-
-                    // Get he initial message
-                    let mut msg = format!("{} {} ({})\n", t.red(), file.display(), err_msg);
-
-                    // Get the last line
-                    let line = program.lines().last().unwrap();
-
-                    // Update message
-                    msg += format!("\n{: >5}|{}", program.lines().count(), line.green()).as_str();
 
-                    // Return pretty message
-                    return msg;
-                }
-                // File position non existent
-                FileLocation::None => {
-                    // Get he initial message
-                    let msg = format!("{} ({})", t.red(), err_msg);
-                    return msg;
-                }
+                json += &format!(
+                    "{{{}\"msg\":{}}}",
+                    json_location(&note.1),
+                    json_string(&note.0)
+                );
             }
-        }
-
-        pub fn json(&self) -> String {
-            let mut json = String::from("{");
-
-            match &self.1 {
-                FileLocation::Loc {
-                    file,
-                    start_line,
-                    end_line,
-                    start_col,
-                    end_col,
-                } => {
-                    json += &format!(
-                        "\"loc\":\"{}:{start_line}:{start_col}-{end_line}:{end_col}\",",
-                        file.display()
-                    )
-                }
-                FileLocation::End { file } => json += &format!("\"loc\":\"{}\",", file.display()),
-                FileLocation::None => {}
-            };
 
-            json += &format!("\"msg\":{:?}}}", self.0);
+            json += "]}";
             return json;
         }
     };
@@ -130,3 +61,17 @@ macro_rules! from_for_err_macro {
         }
     };
 }
+
+macro_rules! new_macro {
+    () => {
+        pub fn new(msg: String, loc: FileLocation, code: ErrorCode) -> Self {
+            return Self(msg, loc, code, Vec::new());
+        }
+
+        // Add a note pointing at a related location
+        pub fn with_note(mut self, msg: String, loc: FileLocation) -> Self {
+            self.3.push(Note(msg, loc));
+            return self;
+        }
+    };
+}
@@ -1,63 +1,150 @@
 use colored::*;
-use std::fs;
-use std::path::PathBuf;
+use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[macro_use]
 mod macros;
+pub mod codes;
+pub mod source_map;
+pub mod suggest;
+pub use codes::ErrorCode;
+pub use source_map::SourceId;
+
+static JSON_MESSAGES: AtomicBool = AtomicBool::new(false);
+
+// Choose whether errors are reported as JSON instead of pretty text
+pub fn set_json_messages(json: bool) {
+    JSON_MESSAGES.store(json, Ordering::Relaxed);
+}
+
+pub fn json_messages() -> bool {
+    return JSON_MESSAGES.load(Ordering::Relaxed);
+}
+
+// Quote text as a JSON string
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            '\r' => json += "\\r",
+            '\t' => json += "\\t",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    return json;
+}
+
+// Process exit status for each stage that can fail
+pub const EXIT_MANAGER: i32 = 2;
+pub const EXIT_LEXER: i32 = 3;
+pub const EXIT_PARSER: i32 = 4;
+pub const EXIT_APARSER: i32 = 5;
+pub const EXIT_RUNTIME: i32 = 6;
+pub const EXIT_LINT: i32 = 7;
+pub const EXIT_FORMAT: i32 = 8;
 
 #[derive(Debug)]
-pub struct ManagerError(pub String, pub FileLocation);
+pub struct ManagerError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {ManagerError}
 impl ManagerError {
-    prettify_macro! {"Manager Error"}
+    new_macro! {}
+    prettify_macro! {"Manager Error", "manager"}
 }
 
 #[derive(Debug)]
-pub struct LexerError(pub String, pub FileLocation);
+pub struct LexerError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {LexerError}
 impl LexerError {
-    prettify_macro! {"Lexer Error"}
+    new_macro! {}
+    prettify_macro! {"Lexer Error", "lexer"}
 }
 
 #[derive(Debug)]
-pub struct ParserError(pub String, pub FileLocation);
+pub struct ParserError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {ParserError}
 impl ParserError {
-    prettify_macro! {"Parser Error"}
+    new_macro! {}
+    prettify_macro! {"Parser Error", "parser"}
 }
 
-pub struct RuntimeError(pub String, pub FileLocation);
+pub struct RuntimeError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {RuntimeError}
 impl RuntimeError {
-    prettify_macro! {"Runtime Error"}
+    new_macro! {}
+    prettify_macro! {"Runtime Error", "runtime"}
 }
 
-pub struct AParserError(pub String, pub FileLocation);
+pub struct AParserError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {AParserError}
 impl AParserError {
-    prettify_macro! {"Active Parser Error"}
+    new_macro! {}
+    prettify_macro! {"Active Parser Error", "aparser"}
 }
 
-pub struct LspError(pub String, pub FileLocation);
+pub struct LspError(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
 from_for_err_macro! {LspError}
 impl LspError {
-    prettify_macro! {"Lsp Error"}
+    new_macro! {}
+    prettify_macro! {"Lsp Error", "lsp"}
 }
 
+pub struct LintWarning(pub String, pub FileLocation, pub ErrorCode, pub Vec<Note>);
+impl LintWarning {
+    prettify_macro! {"Warning", "lint", "warning", Color::Yellow}
+}
+
+// A secondary message pointing at another location, such as where the name an
+// error is about was declared
+#[derive(Debug, Clone)]
+pub struct Note(pub String, pub FileLocation);
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum FileLocation {
     Loc {
-        file: PathBuf,
+        file: SourceId,
         start_line: usize,
         end_line: usize,
         start_col: usize,
         end_col: usize,
     },
     End {
-        file: PathBuf,
+        file: SourceId,
     },
     None,
 }
+// A location as `path:line:col-line:col`, with lines counted from one
+impl Display for FileLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileLocation::Loc {
+                file,
+                start_line,
+                end_line,
+                start_col,
+                end_col,
+            } => write!(
+                f,
+                "{}:{}:{}-{}:{}",
+                source_map::source_path(*file).display(),
+                start_line + 1,
+                start_col,
+                end_line + 1,
+                end_col
+            ),
+            FileLocation::End { file } => {
+                write!(f, "{}:end", source_map::source_path(*file).display())
+            }
+            FileLocation::None => write!(f, "None"),
+        }
+    }
+}
 impl FileLocation {
     pub fn start(&self) -> (usize, usize) {
         match self {
@@ -71,9 +158,137 @@ impl FileLocation {
     }
 }
 
+// Show an error or note message along with the source lines it refers to
+fn render(title: ColoredString, msg: &str, loc: &FileLocation, highlight: Color) -> String {
+    match loc {
+        FileLocation::Loc {
+            file,
+            start_line,
+            end_line,
+            start_col,
+            end_col,
+        } => {
+            let program = source_map::source_text(*file);
+            let lines = program.lines().collect::<Vec<&str>>();
+
+            // Create initial message
+            let mut text = format!(
+                "{} {}:{}:{} ({})\n",
+                title,
+                source_map::source_path(*file).display(),
+                start_line + 1,
+                start_col + 1,
+                msg
+            );
+
+            // Show the lines the location covers, and the one before it
+            let first_line = start_line.saturating_sub(1);
+            for line_num in first_line..=*end_line {
+                let Some(line) = lines.get(line_num) else {
+                    break;
+                };
+
+                let (start, end) = if line_num < *start_line {
+                    (0, 0)
+                } else {
+                    let start = match line_num == *start_line {
+                        true => start_col.saturating_sub(1),
+                        false => 0,
+                    };
+                    let end = match line_num == *end_line {
+                        true => *end_col,
+                        false => usize::MAX,
+                    };
+                    (start, end)
+                };
+
+                let (good1, bad, good2) = split_cols(line, start, end);
+                text += &format!(
+                    "\n{: >5}|{}{}{}",
+                    line_num + 1,
+                    good1.green(),
+                    bad.color(highlight),
+                    good2.green()
+                );
+            }
+
+            return text;
+        }
+        FileLocation::End { file } => {
+            let program = source_map::source_text(*file);
+            let path = source_map::source_path(*file);
+            let mut text = format!("{} {} ({})\n", title, path.display(), msg);
+
+            // Show the last line
+            if let Some(line) = program.lines().last() {
+                text += &format!("\n{: >5}|{}", program.lines().count(), line.green());
+            }
+
+            return text;
+        }
+        FileLocation::None => return format!("{} ({})", title, msg),
+    }
+}
+
+// Split a line into the text before, within and after a range of columns
+fn split_cols(line: &str, start: usize, end: usize) -> (&str, &str, &str) {
+    let byte = |col: usize| match line.char_indices().nth(col) {
+        Some((idx, _)) => idx,
+        None => line.len(),
+    };
+
+    let start = byte(start);
+    let end = byte(end).max(start);
+    return (&line[..start], &line[start..end], &line[end..]);
+}
+
+// JSON fields for a location, ending in a comma
+fn json_location(loc: &FileLocation) -> String {
+    match loc {
+        FileLocation::Loc {
+            file,
+            start_line,
+            end_line,
+            start_col,
+            end_col,
+        } => {
+            let path = source_map::source_path(*file).display().to_string();
+            let loc = format!("{path}:{start_line}:{start_col}-{end_line}:{end_col}");
+
+            // `loc` keeps lines from zero for existing readers. Lines and
+            // columns in the span start at one
+            return format!(
+                "\"loc\":{},\"span\":{{\"file\":{},\"start_line\":{},\"start_col\":{start_col},\"end_line\":{},\"end_col\":{end_col}}},",
+                json_string(&loc),
+                json_string(&path),
+                start_line + 1,
+                end_line + 1
+            );
+        }
+        FileLocation::End { file } => {
+            let path = source_map::source_path(*file).display().to_string();
+            let file = json_string(&path);
+            return format!("\"loc\":{file},\"span\":{{\"file\":{file}}},");
+        }
+        FileLocation::None => return "\"span\":null,".to_string(),
+    }
+}
+
 pub enum ErrorType {
+    Manager(ManagerError),
     Lsp(LspError),
     Parser(ParserError),
     AParser(AParserError),
     Lexer(LexerError),
 }
+impl ErrorType {
+    pub fn json(&self) -> String {
+        match self {
+            ErrorType::Manager(manager_error) => return manager_error.json(),
+            ErrorType::Lsp(lsp_error) => return lsp_error.json(),
+            ErrorType::Parser(parser_error) => return parser_error.json(),
+            ErrorType::AParser(aparser_error) => return aparser_error.json(),
+            ErrorType::Lexer(lexer_error) => return lexer_error.json(),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// Identifies a source in the source map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceId(usize);
+
+struct Source {
+    path: PathBuf,
+    text: String,
+}
+
+// Every source loaded while running, so errors can show the program as it
+// was read, even when it never existed on disk or has changed since
+pub struct SourceMap {
+    sources: Vec<Source>,
+}
+
+static SOURCE_MAP: Mutex<SourceMap> = Mutex::new(SourceMap {
+    sources: Vec::new(),
+});
+
+// Add a source, returning the id its locations refer to it by. A path that
+// was added before keeps its id and takes the new text, so reading a file
+// again does not grow the map.
+pub fn add_source(path: PathBuf, text: String) -> SourceId {
+    let mut map = SOURCE_MAP.lock().unwrap();
+    if let Some(idx) = map.sources.iter().position(|source| source.path == path) {
+        map.sources[idx].text = text;
+        return SourceId(idx);
+    }
+
+    map.sources.push(Source { path, text });
+    return SourceId(map.sources.len() - 1);
+}
+
+// Add a source kept apart from any other with the same path, such as each
+// entry typed into the REPL
+pub fn add_entry(path: PathBuf, text: String) -> SourceId {
+    let mut map = SOURCE_MAP.lock().unwrap();
+    map.sources.push(Source { path, text });
+    return SourceId(map.sources.len() - 1);
+}
+
+pub fn source_path(id: SourceId) -> PathBuf {
+    return SOURCE_MAP.lock().unwrap().sources[id.0].path.clone();
+}
+
+pub fn source_text(id: SourceId) -> String {
+    return SOURCE_MAP.lock().unwrap().sources[id.0].text.clone();
+}
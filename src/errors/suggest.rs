@@ -0,0 +1,78 @@
+// Suggest the closest name to a misspelt one. Names from an imported file
+// carry its prefix, so only names the misspelt one could refer to are used.
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    let (prefix, base) = split_prefix(name);
+    let max_distance = base.chars().count().max(3) / 3;
+
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        let candidate = match split_prefix(candidate) {
+            ("", candidate) => candidate,
+            (p, candidate) if p == prefix => candidate,
+            _ => continue,
+        };
+
+        if candidate == base {
+            continue;
+        }
+
+        let distance = edit_distance(&base.to_lowercase(), &candidate.to_lowercase());
+        let prefixed = candidate.chars().count() >= 3
+            && (base.starts_with(candidate) || candidate.starts_with(base));
+
+        if distance > max_distance && !prefixed {
+            continue;
+        }
+
+        // Prefer the closest name, then the first alphabetically so the
+        // suggestion does not depend on hash map order
+        best = match best {
+            Some((d, b)) if (d, b) <= (distance, candidate) => Some((d, b)),
+            _ => Some((distance, candidate)),
+        };
+    }
+
+    match best {
+        Some((_, suggestion)) => return format!(" Did you mean `{suggestion}`?"),
+        None => return String::new(),
+    }
+}
+
+fn split_prefix(name: &str) -> (&str, &str) {
+    match name.rfind("::") {
+        Some(idx) => return (&name[..idx + 2], &name[idx + 2..]),
+        None => return ("", name),
+    }
+}
+
+// Edit distance between two strings, counting a swap of neighbouring
+// characters as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+
+            rows[i][j] = distance;
+        }
+    }
+
+    return rows[a.len()][b.len()];
+}
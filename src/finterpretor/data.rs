@@ -26,6 +26,11 @@ impl DataH {
         self.0.get_mut(idx).unwrap()
     }
 
+    #[cfg_attr(not(feature = "no-inline"), inline(always))]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
     #[cfg_attr(not(feature = "no-inline"), inline(always))]
     pub fn get_valid_data(&self) -> Vec<(&usize, &Cell)> {
         (&self.0).into_iter().collect()
@@ -191,7 +191,7 @@ impl Debugger<'_> {
         )
     }
 
-    pub fn debug(&mut self) -> Result<(), RuntimeError> {
+    pub fn debug(&mut self) -> Result<i32, RuntimeError> {
         self.clear_terminal();
 
         loop {
@@ -222,10 +222,13 @@ impl Debugger<'_> {
                 }
 
                 self.commands_run += 1;
+                if !self.complete {
+                    self.runner.check_limits(self.commands_run as u64)?;
+                }
             }
         }
 
-        return Ok(());
+        return self.runner.exit_status();
     }
 
     fn clear_terminal(&self) {
@@ -1,12 +1,18 @@
 mod data;
 mod debugger;
-use std::io::stdin;
+pub mod sandbox;
+use std::env;
+use std::fs;
+use std::io::{self, stdin, Write};
+use std::path::Path;
+use std::process::Command;
 
 use crate::active_parser::names as nms;
-use crate::errors::{FileLocation, RuntimeError};
-use crate::flat_ir::{FlatProgram, VarAdress, CMD};
+use crate::errors::{codes, FileLocation, RuntimeError};
+use crate::flat_ir::{FlatProgram, VNameConverter, VarAdress, CMD};
 use data::Data;
 use rustc_hash::FxHashMap;
+use sandbox::Sandbox;
 
 #[derive(Debug, Clone)]
 pub enum Value {
@@ -46,6 +52,78 @@ impl Value {
             _ => panic!(),
         }
     }
+
+    #[cfg_attr(not(feature = "no-inline"), inline(always))]
+    fn float<'a>(&'a self, runner: &'a Runner) -> &'a f32 {
+        match self {
+            Value::Float(f) => f,
+            Value::Ptr(to) => &runner.data[to].0.float(runner),
+            _ => panic!(),
+        }
+    }
+}
+
+// Results that are NaN or infinite are outside the domain of the function
+fn math_result(
+    op: &str,
+    args: &[f32],
+    result: f32,
+    loc: &FileLocation,
+) -> Result<f32, RuntimeError> {
+    if result.is_finite() {
+        return Ok(result);
+    }
+
+    let args = args
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    return Err(RuntimeError::new(
+        format!("{op}.({args}) is not a finite number."),
+        loc.clone(),
+        codes::R0003,
+    ));
+}
+
+fn file_error(action: &str, path: &str, err: io::Error) -> String {
+    return format!("Could not {action} \"{path}\": {err}");
+}
+
+// Statuses outside of [0, 255] would be truncated by the OS, so 256 would
+// look like success
+fn check_exit_status(code: i32, loc: &FileLocation) -> Result<i32, RuntimeError> {
+    match code {
+        0..=255 => Ok(code),
+        _ => Err(RuntimeError::new(
+            format!(
+                "Exit status {code} is out of range. Exit statuses must be in the range [0, 255]."
+            ),
+            loc.clone(),
+            codes::R0009,
+        )),
+    }
+}
+
+fn index_out_of_range(idx: i32, arr_len: i32, loc: &FileLocation) -> RuntimeError {
+    if arr_len > 0 {
+        RuntimeError::new(
+            format!(
+                "{} is out of the range of the array. Array range is [0, {}].",
+                idx,
+                arr_len - 1
+            ),
+            loc.clone(),
+            codes::R0001,
+        )
+    } else {
+        RuntimeError::new(
+            format!("{} is out of the range of the array. Array is empty.", idx),
+            loc.clone(),
+            codes::R0001,
+        )
+    }
 }
 
 struct GlobalCounter(usize);
@@ -72,9 +150,11 @@ struct Runner<'a> {
     scopes: Vec<Vec<Value>>,
     data: Data,
     gc: GlobalCounter,
+    exit_code: Option<i32>,
+    sandbox: &'a Sandbox,
 }
 impl<'a> Runner<'a> {
-    fn new(prog: &'a FlatProgram, args: &Vec<String>) -> Self {
+    fn new(prog: &'a FlatProgram, args: &Vec<String>, sandbox: &'a Sandbox) -> Self {
         let data = Data::default();
         let gc = GlobalCounter::new();
         let args = args.into_iter().map(|x| Value::Str(x.clone())).collect();
@@ -89,6 +169,8 @@ impl<'a> Runner<'a> {
             prog,
             gc,
             data,
+            exit_code: None,
+            sandbox,
         }
     }
 
@@ -100,9 +182,73 @@ impl<'a> Runner<'a> {
         }
     }
 
-    fn run(&mut self) -> Result<(), RuntimeError> {
-        while !self.run_command()? {}
-        return Ok(());
+    fn run(&mut self) -> Result<i32, RuntimeError> {
+        let mut steps: u64 = 0;
+
+        while !self.run_command()? {
+            steps += 1;
+            self.check_limits(steps)?;
+        }
+
+        return self.exit_status();
+    }
+
+    // Limits of the sandbox checked between commands
+    fn check_limits(&self, steps: u64) -> Result<(), RuntimeError> {
+        self.sandbox.check_steps(steps)?;
+        return self.reserve(0, 0, &FileLocation::None);
+    }
+
+    // Checks that the heap still fits in `--max-memory` with `cells` more
+    // heap values and `bytes` more bytes of strings
+    fn reserve(&self, cells: usize, bytes: usize, loc: &FileLocation) -> Result<(), RuntimeError> {
+        if self.sandbox.max_memory.is_none() {
+            return Ok(());
+        }
+
+        let cells = self.data.len().saturating_add(cells);
+        let used = cells
+            .saturating_mul(std::mem::size_of::<Cell>())
+            .saturating_add(bytes);
+        return self.sandbox.check_memory(used, loc);
+    }
+
+    fn resume(prog: &'a FlatProgram, state: &mut ReplState, sandbox: &'a Sandbox) -> Self {
+        state.scopes.resize(prog.n_scopes, Vec::new());
+
+        Self {
+            current_postion: prog.start_point,
+            stack: Vec::new(),
+            refer_stack: Vec::new(),
+            scopes: std::mem::take(&mut state.scopes),
+            prog,
+            gc: std::mem::replace(&mut state.gc, GlobalCounter::new()),
+            data: std::mem::replace(&mut state.data, Data::default()),
+            exit_code: None,
+            sandbox,
+        }
+    }
+
+    fn suspend(self, state: &mut ReplState) {
+        state.scopes = self.scopes;
+        state.gc = self.gc;
+        state.data = self.data;
+    }
+
+    // Status from @exit, otherwise the int returned from @main
+    fn exit_status(&self) -> Result<i32, RuntimeError> {
+        if let Some(code) = self.exit_code {
+            return Ok(code);
+        }
+
+        // The int returned by @main
+        match self.stack.last() {
+            Some(value) => match self.reduct(value) {
+                Value::Int(code) => check_exit_status(*code, &FileLocation::None),
+                _ => Ok(0),
+            },
+            None => Ok(0),
+        }
     }
 
     #[cfg_attr(not(feature = "no-inline"), inline(always))]
@@ -188,6 +334,7 @@ impl<'a> Runner<'a> {
                 match (aa, bb) {
                     (Value::Int(i2), Value::Int(i1)) => Value::Bool(i1 <= i2),
                     (Value::Float(f2), Value::Float(f1)) => Value::Bool(f1 <= f2),
+                    (Value::Str(s2), Value::Str(s1)) => Value::Bool(s1 <= s2),
                     _ => panic!(),
                 }
             }
@@ -200,6 +347,7 @@ impl<'a> Runner<'a> {
                 match (aa, bb) {
                     (Value::Int(i2), Value::Int(i1)) => Value::Bool(i1 < i2),
                     (Value::Float(f2), Value::Float(f1)) => Value::Bool(f1 < f2),
+                    (Value::Str(s2), Value::Str(s1)) => Value::Bool(s1 < s2),
                     _ => panic!(),
                 }
             }
@@ -224,7 +372,10 @@ impl<'a> Runner<'a> {
                 match (aa, bb) {
                     (Value::Int(i2), Value::Int(i1)) => Value::Int(i1 + i2),
                     (Value::Float(f2), Value::Float(f1)) => Value::Float(f1 + f2),
-                    (Value::Str(s2), Value::Str(s1)) => Value::Str(s1.clone() + &s2),
+                    (Value::Str(s2), Value::Str(s1)) => {
+                        self.reserve(0, s1.len() + s2.len(), loc)?;
+                        Value::Str(s1.clone() + &s2)
+                    }
                     _ => panic!(),
                 }
             }
@@ -287,9 +438,10 @@ impl<'a> Runner<'a> {
                     Value::Str(s) => Value::Int(match s.parse() {
                         Ok(i) => i,
                         Err(_) => {
-                            return Err(RuntimeError(
+                            return Err(RuntimeError::new(
                                 format!("\"{}\" can not be converted into an integer.", s),
                                 loc.clone(),
+                                codes::R0002,
                             ))
                         }
                     }),
@@ -307,9 +459,10 @@ impl<'a> Runner<'a> {
                     Value::Str(s) => Value::Float(match s.parse() {
                         Ok(i) => i,
                         Err(_) => {
-                            return Err(RuntimeError(
+                            return Err(RuntimeError::new(
                                 format!("\"{}\" can not be converted into a float.", s),
                                 loc.clone(),
+                                codes::R0002,
                             ))
                         }
                     }),
@@ -363,10 +516,21 @@ impl<'a> Runner<'a> {
                 let bb = self.reduct(&b);
 
                 match (aa, bb) {
-                    (Value::Int(i2), Value::Int(i1)) => Value::Int(i1.pow(match (*i2).try_into() {
-                        Ok(i2) => i2,
-                        Err(_) =>  return Err(RuntimeError(format!("Right side of int exponent must be positive integer. Found {i2}. Convert to float to avoid this."), loc.clone())),
-                    })),
+                    (Value::Int(i2), Value::Int(i1)) => {
+                        let exponent = match (*i2).try_into() {
+                            Ok(exponent) => exponent,
+                            Err(_) => {
+                                return Err(RuntimeError::new(
+                                    format!(
+                                        "Right side of int exponent must be positive integer. Found {i2}. Convert to float to avoid this."
+                                    ),
+                                    loc.clone(),
+                                    codes::R0003,
+                                ))
+                            }
+                        };
+                        Value::Int(i1.pow(exponent))
+                    }
                     (Value::Float(f2), Value::Float(f1)) => Value::Float(f1.powf(*f2)),
                     _ => panic!(),
                 }
@@ -380,6 +544,7 @@ impl<'a> Runner<'a> {
                 match (aa, bb) {
                     (Value::Int(i2), Value::Int(i1)) => Value::Bool(i1 > i2),
                     (Value::Float(f2), Value::Float(f1)) => Value::Bool(f1 > f2),
+                    (Value::Str(s2), Value::Str(s1)) => Value::Bool(s1 > s2),
                     _ => panic!(),
                 }
             }
@@ -392,6 +557,7 @@ impl<'a> Runner<'a> {
                 match (aa, bb) {
                     (Value::Int(i2), Value::Int(i1)) => Value::Bool(i1 >= i2),
                     (Value::Float(f2), Value::Float(f1)) => Value::Bool(f1 >= f2),
+                    (Value::Str(s2), Value::Str(s1)) => Value::Bool(s1 >= s2),
                     _ => panic!(),
                 }
             }
@@ -401,6 +567,70 @@ impl<'a> Runner<'a> {
 
                 match aa {
                     Value::Bool(b) => Value::Bool(!b),
+                    Value::Int(i) => Value::Int(!i),
+                    _ => panic!(),
+                }
+            }
+            nms::F_BAND => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let aa = self.reduct(&a);
+                let bb = self.reduct(&b);
+
+                match (aa, bb) {
+                    (Value::Int(i2), Value::Int(i1)) => Value::Int(i1 & i2),
+                    _ => panic!(),
+                }
+            }
+            nms::F_BOR => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let aa = self.reduct(&a);
+                let bb = self.reduct(&b);
+
+                match (aa, bb) {
+                    (Value::Int(i2), Value::Int(i1)) => Value::Int(i1 | i2),
+                    _ => panic!(),
+                }
+            }
+            nms::F_XOR => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let aa = self.reduct(&a);
+                let bb = self.reduct(&b);
+
+                match (aa, bb) {
+                    (Value::Int(i2), Value::Int(i1)) => Value::Int(i1 ^ i2),
+                    _ => panic!(),
+                }
+            }
+            nms::F_SHL | nms::F_SHR => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let aa = self.reduct(&a);
+                let bb = self.reduct(&b);
+
+                match (aa, bb) {
+                    (Value::Int(i2), Value::Int(i1)) => {
+                        let shifted = match u32::try_from(*i2) {
+                            Ok(by) if op == nms::F_SHL => i1.checked_shl(by),
+                            Ok(by) => i1.checked_shr(by),
+                            Err(_) => None,
+                        };
+
+                        match shifted {
+                            Some(i) => Value::Int(i),
+                            None => {
+                                return Err(RuntimeError::new(
+                                    format!(
+                                        "Cannot shift by {i2}. Shift amount must be in the range [0, 31]."
+                                    ),
+                                    loc.clone(),
+                                    codes::R0003,
+                                ))
+                            }
+                        }
+                    }
                     _ => panic!(),
                 }
             }
@@ -431,7 +661,7 @@ impl<'a> Runner<'a> {
                 let aa = self.reduct(&a);
 
                 match aa {
-                    Value::Str(s) => Value::Int(s.len().try_into().unwrap()),
+                    Value::Str(s) => Value::Int(s.chars().count().try_into().unwrap()),
                     Value::Array(a) => Value::Int(a.len().try_into().unwrap()),
                     _ => panic!(),
                 }
@@ -448,24 +678,7 @@ impl<'a> Runner<'a> {
                         let arr_len = arr.len() as i32;
 
                         if idx < 0 || idx + 1 > arr_len {
-                            if arr_len > 0 {
-                                return Err(RuntimeError(
-                                    format!(
-                                        "{} is out of the range of the array. Array range is [0, {}].",
-                                        idx,
-                                        arr_len - 1
-                                    ),
-                                    loc.clone(),
-                                ));
-                            } else {
-                                return Err(RuntimeError(
-                                    format!(
-                                        "{} is out of the range of the array. Array is empty.",
-                                        idx,
-                                    ),
-                                    loc.clone(),
-                                ));
-                            }
+                            return Err(index_out_of_range(idx, arr_len, loc));
                         }
 
                         arr[idx as usize].clone()
@@ -474,11 +687,9 @@ impl<'a> Runner<'a> {
                 }
             }
             nms::F_APPEND => {
-                let key = self.gc.next();
                 let a = self.stack_pop();
                 let mut b = self.stack_pop();
-                let aa = self.reduct(&a).clone();
-                self.data.insert(key, Cell(aa.clone(), 1));
+                let key = self.adopt(a);
 
                 let bb = self.mut_reduct(&mut b);
 
@@ -501,39 +712,619 @@ impl<'a> Runner<'a> {
                         let arr_len = arr.len() as i32;
 
                         if idx < 0 || idx + 1 > arr_len {
-                            if arr_len > 0 {
-                                return Err(RuntimeError(
-                                    format!(
-                                        "{} is out of the range of the array. Array range is [0, {}].",
-                                        idx,
-                                        arr_len - 1
-                                    ),
-                                    loc.clone(),
-                                ));
-                            } else {
-                                return Err(RuntimeError(
-                                    format!(
-                                        "{} is out of the range of the array. Array is empty.",
-                                        idx,
-                                    ),
-                                    loc.clone(),
-                                ));
-                            }
+                            return Err(index_out_of_range(idx, arr_len, loc));
+                        }
+
+                        let element = arr.remove(idx.try_into().unwrap());
+                        self.take_element(element)
+                    }
+                    _ => panic!(),
+                }
+            }
+            nms::F_INSERT => {
+                let a = self.stack_pop();
+                let idx = self.stack_pop();
+                let idx = *idx.int(self);
+
+                let mut b = self.stack_pop();
+                let arr_len = match self.reduct(&b) {
+                    Value::Array(arr) => arr.len() as i32,
+                    _ => panic!(),
+                };
+
+                if idx < 0 || idx > arr_len {
+                    return Err(RuntimeError::new(
+                        format!(
+                            "{} is out of the range for insertion. Insertion range is [0, {}].",
+                            idx, arr_len
+                        ),
+                        loc.clone(),
+                        codes::R0001,
+                    ));
+                }
+
+                let key = self.adopt(a);
+
+                match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => arr.insert(idx as usize, Value::Ptr(key)),
+                    _ => panic!(),
+                };
+
+                Value::Null
+            }
+            nms::F_POP => {
+                let mut b = self.stack_pop();
+
+                let element = match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => arr.pop(),
+                    _ => panic!(),
+                };
+
+                match element {
+                    Some(element) => self.take_element(element),
+                    None => {
+                        return Err(RuntimeError::new(
+                            format!("Cannot pop from an empty array."),
+                            loc.clone(),
+                            codes::R0001,
+                        ))
+                    }
+                }
+            }
+            nms::F_SLICE => {
+                let end = self.stack_pop();
+                let end = *end.int(self);
+                let start = self.stack_pop();
+                let start = *start.int(self);
+                let b = self.stack_pop();
+
+                let elements = match self.reduct(&b) {
+                    Value::Array(arr) => {
+                        let arr_len = arr.len() as i32;
+                        if start < 0 || end < start || end > arr_len {
+                            return Err(RuntimeError::new(
+                                format!(
+                                    "Slice [{}, {}) is out of the range of the array. Array length is {}.",
+                                    start, end, arr_len
+                                ),
+                                loc.clone(),
+                                codes::R0001,
+                            ));
                         }
 
-                        arr.remove(idx.try_into().unwrap());
+                        arr[start as usize..end as usize].to_vec()
+                    }
+                    _ => panic!(),
+                };
+
+                // Elements are copied, so the slice does not share them
+                self.reserve(elements.len() + 1, 0, loc)?;
+                let mut keys = Vec::with_capacity(elements.len());
+                for element in elements {
+                    let element = self.reduct(&element).clone();
+                    self.share(&element);
+
+                    let key = self.gc.next();
+                    self.data.insert(key, Cell(element, 1));
+                    keys.push(Value::Ptr(key));
+                }
+
+                let key = self.gc.next();
+                self.data.insert(key, Cell(Value::Array(keys), 0));
+                Value::Ptr(key)
+            }
+            nms::F_REVERSE => {
+                let mut b = self.stack_pop();
+
+                match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => arr.reverse(),
+                    _ => panic!(),
+                };
+
+                Value::Null
+            }
+            nms::F_CONTAINS | nms::F_INDEXOF => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+
+                let position = match self.reduct(&b) {
+                    Value::Array(arr) => arr.iter().position(|x| self.values_eq(x, &a)),
+                    _ => panic!(),
+                };
+                self.dispose(a);
 
-                        Value::Null
+                match op {
+                    nms::F_CONTAINS => Value::Bool(position.is_some()),
+                    _ => Value::Int(match position {
+                        Some(position) => position as i32,
+                        None => -1,
+                    }),
+                }
+            }
+            nms::F_SORT => {
+                let mut b = self.stack_pop();
+
+                let mut elements = match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => std::mem::take(arr),
+                    _ => panic!(),
+                };
+
+                elements.sort_by(|x, y| match (self.reduct(x), self.reduct(y)) {
+                    (Value::Int(i1), Value::Int(i2)) => i1.cmp(i2),
+                    (Value::Float(f1), Value::Float(f2)) => f1.total_cmp(f2),
+                    (Value::Str(s1), Value::Str(s2)) => s1.cmp(s2),
+                    _ => panic!(),
+                });
+
+                match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => *arr = elements,
+                    _ => panic!(),
+                };
+
+                Value::Null
+            }
+            nms::F_JOIN => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+
+                let arr = match self.reduct(&b) {
+                    Value::Array(arr) => arr,
+                    _ => panic!(),
+                };
+                let parts: Vec<&str> = arr.iter().map(|x| x.string(self).as_str()).collect();
+                let sep = a.string(self);
+
+                let bytes = parts.iter().map(|x| x.len()).sum::<usize>()
+                    + sep.len() * parts.len().saturating_sub(1);
+                self.reserve(0, bytes, loc)?;
+                Value::Str(parts.join(sep))
+            }
+            nms::F_EXTEND => {
+                let a = self.stack_pop();
+                let mut b = self.stack_pop();
+
+                let mut keys = match a {
+                    // The elements of an unowned temporary are moved over
+                    Value::Ptr(key) if self.data[&key].1 == 0 => match self.data.remove(&key).0 {
+                        Value::Array(arr) => arr,
+                        _ => panic!(),
+                    },
+                    _ => {
+                        let elements = match self.reduct(&a) {
+                            Value::Array(arr) => arr.clone(),
+                            _ => panic!(),
+                        };
+                        self.reserve(elements.len(), 0, loc)?;
+
+                        let mut keys = Vec::with_capacity(elements.len());
+                        for element in elements {
+                            let element = self.reduct(&element).clone();
+                            self.share(&element);
+
+                            let key = self.gc.next();
+                            self.data.insert(key, Cell(element, 1));
+                            keys.push(Value::Ptr(key));
+                        }
+
+                        keys
                     }
+                };
+
+                match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => arr.append(&mut keys),
                     _ => panic!(),
+                };
+
+                Value::Null
+            }
+            nms::F_CLEAR => {
+                let mut b = self.stack_pop();
+
+                let elements = match self.mut_reduct(&mut b) {
+                    Value::Array(arr) => std::mem::take(arr),
+                    _ => panic!(),
+                };
+
+                for element in elements {
+                    if let Value::Ptr(key) = element {
+                        self.drop_ref(key);
+                    }
                 }
+
+                Value::Null
+            }
+            nms::F_SPLIT => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let sep = a.string(self);
+
+                if sep.is_empty() {
+                    return Err(RuntimeError::new(
+                        format!("Cannot split on an empty separator. Use @chars to get the characters of a string."),
+                        loc.clone(),
+                        codes::R0004,
+                    ));
+                }
+
+                let parts = b
+                    .string(self)
+                    .split(sep.as_str())
+                    .map(|x| Value::Str(x.to_string()))
+                    .collect();
+                self.alloc_array(parts, loc)?
+            }
+            nms::F_TRIM => {
+                let a = self.stack_pop();
+                Value::Str(a.string(self).trim().to_string())
+            }
+            nms::F_SUBSTR => {
+                let end = self.stack_pop();
+                let end = *end.int(self);
+                let start = self.stack_pop();
+                let start = *start.int(self);
+                let b = self.stack_pop();
+                let s = b.string(self);
+
+                let s_len = s.chars().count() as i32;
+                if start < 0 || end < start || end > s_len {
+                    return Err(RuntimeError::new(
+                        format!(
+                            "Substring [{}, {}) is out of the range of the string. String length is {}.",
+                            start, end, s_len
+                        ),
+                        loc.clone(),
+                        codes::R0001,
+                    ));
+                }
+
+                Value::Str(
+                    s.chars()
+                        .skip(start as usize)
+                        .take((end - start) as usize)
+                        .collect(),
+                )
+            }
+            nms::F_FIND => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let s = b.string(self);
+
+                // Report the position in characters rather than bytes
+                Value::Int(match s.find(a.string(self).as_str()) {
+                    Some(byte_idx) => s[..byte_idx].chars().count() as i32,
+                    None => -1,
+                })
+            }
+            nms::F_REPLACE => {
+                let to = self.stack_pop();
+                let from = self.stack_pop();
+                let b = self.stack_pop();
+                let (s, from, to) = (b.string(self), from.string(self), to.string(self));
+
+                if !from.is_empty() && to.len() > from.len() {
+                    let grows = s.matches(from.as_str()).count() * (to.len() - from.len());
+                    self.reserve(0, s.len() + grows, loc)?;
+                }
+                Value::Str(s.replace(from.as_str(), to))
+            }
+            nms::F_STARTSWITH => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                Value::Bool(b.string(self).starts_with(a.string(self).as_str()))
+            }
+            nms::F_ENDSWITH => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                Value::Bool(b.string(self).ends_with(a.string(self).as_str()))
+            }
+            nms::F_UPPER => {
+                let a = self.stack_pop();
+                Value::Str(a.string(self).to_uppercase())
+            }
+            nms::F_LOWER => {
+                let a = self.stack_pop();
+                Value::Str(a.string(self).to_lowercase())
+            }
+            nms::F_CHARS => {
+                let a = self.stack_pop();
+                let chars = a
+                    .string(self)
+                    .chars()
+                    .map(|x| Value::Str(x.to_string()))
+                    .collect();
+                self.alloc_array(chars, loc)?
+            }
+            nms::F_REPEAT => {
+                let a = self.stack_pop();
+                let times = *a.int(self);
+                let b = self.stack_pop();
+
+                match usize::try_from(times) {
+                    Ok(times) => {
+                        let s = b.string(self);
+                        self.reserve(0, s.len().saturating_mul(times), loc)?;
+                        Value::Str(s.repeat(times))
+                    }
+                    Err(_) => {
+                        return Err(RuntimeError::new(
+                            format!(
+                                "Cannot repeat a string {times} times. Count must not be negative."
+                            ),
+                            loc.clone(),
+                            codes::R0004,
+                        ))
+                    }
+                }
+            }
+            nms::F_ABS => {
+                let a = self.stack_pop();
+
+                match self.reduct(&a) {
+                    Value::Int(i) => match i.checked_abs() {
+                        Some(result) => Value::Int(result),
+                        None => {
+                            return Err(RuntimeError::new(
+                                format!("{i}.{op}.() does not fit in an int."),
+                                loc.clone(),
+                                codes::R0003,
+                            ))
+                        }
+                    },
+                    Value::Float(f) => Value::Float(math_result(op, &[*f], f.abs(), loc)?),
+                    _ => panic!(),
+                }
+            }
+            nms::F_MIN | nms::F_MAX => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+
+                match (self.reduct(&a), self.reduct(&b)) {
+                    (Value::Int(i2), Value::Int(i1)) => match op {
+                        nms::F_MIN => Value::Int(*i1.min(i2)),
+                        _ => Value::Int(*i1.max(i2)),
+                    },
+                    (Value::Float(f2), Value::Float(f1)) => {
+                        let result = match op {
+                            nms::F_MIN => f1.min(*f2),
+                            _ => f1.max(*f2),
+                        };
+
+                        Value::Float(math_result(op, &[*f1, *f2], result, loc)?)
+                    }
+                    _ => panic!(),
+                }
+            }
+            nms::F_SQRT | nms::F_SIN | nms::F_COS | nms::F_TAN | nms::F_LOG => {
+                let a = self.stack_pop();
+                let f = *a.float(self);
+
+                let result = match op {
+                    nms::F_SQRT => f.sqrt(),
+                    nms::F_SIN => f.sin(),
+                    nms::F_COS => f.cos(),
+                    nms::F_TAN => f.tan(),
+                    _ => f.ln(),
+                };
+
+                Value::Float(math_result(op, &[f], result, loc)?)
+            }
+            nms::F_ATAN2 => {
+                let a = self.stack_pop();
+                let f2 = *a.float(self);
+                let b = self.stack_pop();
+                let f1 = *b.float(self);
+
+                Value::Float(math_result(op, &[f1, f2], f1.atan2(f2), loc)?)
+            }
+            nms::F_CLAMP => {
+                let a = self.stack_pop();
+                let high = *a.float(self);
+                let b = self.stack_pop();
+                let low = *b.float(self);
+                let c = self.stack_pop();
+                let f = *c.float(self);
+
+                if low > high || low.is_nan() || high.is_nan() {
+                    return Err(RuntimeError::new(
+                        format!(
+                            "Cannot clamp between {low} and {high}. The lower bound must not be greater than the upper bound."
+                        ),
+                        loc.clone(),
+                        codes::R0003,
+                    ));
+                }
+
+                Value::Float(math_result(op, &[f, low, high], f.clamp(low, high), loc)?)
+            }
+            nms::F_FLOOR | nms::F_CEIL => {
+                let a = self.stack_pop();
+                let f = *a.float(self);
+
+                let result = match op {
+                    nms::F_FLOOR => f.floor(),
+                    _ => f.ceil(),
+                };
+
+                let result = math_result(op, &[f], result, loc)?;
+                if result < i32::MIN as f32 || result >= i32::MAX as f32 {
+                    return Err(RuntimeError::new(
+                        format!("{op}.({f}) does not fit in an int."),
+                        loc.clone(),
+                        codes::R0003,
+                    ));
+                }
+
+                Value::Int(result as i32)
+            }
+            nms::F_READFILE | nms::F_READLINES => {
+                let a = self.stack_pop();
+                let path = a.string(self);
+                self.sandbox.check_read(path, loc)?;
+
+                let content = fs::read_to_string(path).map_err(|err| file_error("read", path, err));
+                match op {
+                    nms::F_READFILE => {
+                        let (value, error) = match content {
+                            Ok(content) => {
+                                self.reserve(1, content.len(), loc)?;
+                                (Value::Str(content), None)
+                            }
+                            Err(err) => (Value::Str(String::new()), Some(err)),
+                        };
+                        self.file_result(Some(value), error)
+                    }
+                    _ => {
+                        let (lines, error) = match content {
+                            Ok(content) => {
+                                let lines =
+                                    content.lines().map(|x| Value::Str(x.to_string())).collect();
+                                (lines, None)
+                            }
+                            Err(err) => (Vec::new(), Some(err)),
+                        };
+                        let lines = self.alloc_array(lines, loc)?;
+                        self.file_result(Some(lines), error)
+                    }
+                }
+            }
+            nms::F_WRITEFILE | nms::F_APPENDFILE => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let path = b.string(self);
+                let content = a.string(self);
+                self.sandbox.check_write(path, loc)?;
+
+                let result = match op {
+                    nms::F_WRITEFILE => fs::write(path, content),
+                    _ => fs::OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(path)
+                        .and_then(|mut file| file.write_all(content.as_bytes())),
+                };
+
+                let error = result.err().map(|err| file_error("write", path, err));
+                self.file_result(None, error)
+            }
+            nms::F_EXISTS => {
+                let a = self.stack_pop();
+                let path = a.string(self);
+                self.sandbox.check_read(path, loc)?;
+
+                Value::Bool(Path::new(path).exists())
+            }
+            nms::F_LISTDIR => {
+                let a = self.stack_pop();
+                let path = a.string(self);
+                self.sandbox.check_read(path, loc)?;
+
+                let names = fs::read_dir(path).and_then(|entries| {
+                    entries
+                        .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+                        .collect::<Result<Vec<String>, io::Error>>()
+                });
+                let (mut names, error) = match names {
+                    Ok(names) => (names, None),
+                    Err(err) => (Vec::new(), Some(file_error("list", path, err))),
+                };
+                names.sort();
+
+                let names = self.alloc_array(names.into_iter().map(Value::Str).collect(), loc)?;
+                self.file_result(Some(names), error)
+            }
+            nms::F_DELETEFILE => {
+                let a = self.stack_pop();
+                let path = a.string(self);
+                self.sandbox.check_write(path, loc)?;
+
+                let error = fs::remove_file(path)
+                    .err()
+                    .map(|err| file_error("delete", path, err));
+                self.file_result(None, error)
+            }
+            nms::F_EXIT => {
+                let a = self.stack_pop();
+                self.exit_code = Some(check_exit_status(*a.int(self), loc)?);
+                Value::Null
+            }
+            nms::F_GETENV | nms::F_HASENV => {
+                self.sandbox.check_env(loc)?;
+                let a = self.stack_pop();
+                let var = env::var(a.string(self));
+
+                match op {
+                    nms::F_GETENV => Value::Str(var.unwrap_or_default()),
+                    _ => Value::Bool(var.is_ok()),
+                }
+            }
+            nms::F_SETENV => {
+                self.sandbox.check_env(loc)?;
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let name = b.string(self);
+                let value = a.string(self);
+
+                if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+                    return Err(RuntimeError::new(
+                        format!("Cannot set environment variable \"{name}\". Names must not be empty or contain '=', and neither may contain NUL."),
+                        loc.clone(),
+                        codes::R0004,
+                    ));
+                }
+
+                env::set_var(name, value);
+                Value::Null
+            }
+            nms::F_RUN => {
+                let a = self.stack_pop();
+                let b = self.stack_pop();
+                let program = b.string(self);
+                self.sandbox.check_run(program, loc)?;
+
+                let args: Vec<&String> = match self.reduct(&a) {
+                    Value::Array(arr) => arr.iter().map(|x| x.string(self)).collect(),
+                    _ => panic!(),
+                };
+
+                let output = match Command::new(program).args(args).output() {
+                    Ok(output) => output,
+                    Err(err) => {
+                        return Err(RuntimeError::new(
+                            format!("Could not run \"{program}\": {err}"),
+                            loc.clone(),
+                            codes::R0006,
+                        ))
+                    }
+                };
+
+                // A process ended by a signal has no status code
+                let status = output.status.code().unwrap_or(-1);
+
+                let mut fields = FxHashMap::default();
+                let mut field = |name: &str, value: Value| {
+                    fields.insert(self.prog.root_fields[name], value);
+                };
+                field(
+                    nms::F_STDOUT,
+                    Value::Str(String::from_utf8_lossy(&output.stdout).to_string()),
+                );
+                field(
+                    nms::F_STDERR,
+                    Value::Str(String::from_utf8_lossy(&output.stderr).to_string()),
+                );
+                field(nms::F_STATUS, Value::Int(status));
+
+                let key = self.gc.next();
+                self.data.insert(key, Cell(Value::Custom(fields), 0));
+                Value::Ptr(key)
             }
             nms::F_READLN => {
                 let mut s = String::new();
                 if let Err(err) = stdin().read_line(&mut s) {
-                    return Err(RuntimeError(
+                    return Err(RuntimeError::new(
                         format!("Could not read: {}", err),
                         loc.clone(),
+                        codes::R0006,
                     ));
                 }
                 Value::Str(s)
@@ -541,10 +1332,207 @@ impl<'a> Runner<'a> {
             _ => panic!(),
         };
 
+        // Strings made by the other built-ins are checked once they exist
+        if let Value::Str(s) = &val {
+            self.reserve(0, s.len(), loc)?;
+        }
+
         self.stack.push(val);
         return Ok(());
     }
 
+    // Move complex values onto the heap and count the new reference
+    fn boxed(&mut self, value: Value) -> Value {
+        match value {
+            Value::Ptr(to) => {
+                self.data.get_mut(&to).1 += 1;
+                Value::Ptr(to)
+            }
+            Value::Array(..) | Value::Custom(..) => {
+                let key = self.gc.next();
+                self.data.insert(key, Cell(value, 1));
+                Value::Ptr(key)
+            }
+            _ => value,
+        }
+    }
+
+    // Result of a file function: whether it worked, the value it gives if
+    // any, and why it failed
+    fn file_result(&mut self, value: Option<Value>, error: Option<String>) -> Value {
+        let mut fields = FxHashMap::default();
+        fields.insert(
+            self.prog.root_fields[nms::F_OK],
+            Value::Bool(error.is_none()),
+        );
+        fields.insert(
+            self.prog.root_fields[nms::F_ERROR],
+            Value::Str(error.unwrap_or_default()),
+        );
+
+        if let Some(value) = value {
+            // The result owns its value
+            if let Value::Ptr(key) = value {
+                self.data.get_mut(&key).1 += 1;
+            }
+            fields.insert(self.prog.root_fields[nms::F_VALUE], value);
+        }
+
+        let key = self.gc.next();
+        self.data.insert(key, Cell(Value::Custom(fields), 0));
+        return Value::Ptr(key);
+    }
+
+    // Box each element and store the new array as an unowned temporary
+    fn alloc_array(
+        &mut self,
+        elements: Vec<Value>,
+        loc: &FileLocation,
+    ) -> Result<Value, RuntimeError> {
+        let bytes = elements
+            .iter()
+            .map(|element| match element {
+                Value::Str(s) => s.len(),
+                _ => 0,
+            })
+            .sum();
+        self.reserve(elements.len() + 1, bytes, loc)?;
+
+        let mut arr = Vec::with_capacity(elements.len());
+        for element in elements {
+            let key = self.gc.next();
+            self.data.insert(key, Cell(element, 1));
+            arr.push(Value::Ptr(key));
+        }
+
+        let key = self.gc.next();
+        self.data.insert(key, Cell(Value::Array(arr), 0));
+        return Ok(Value::Ptr(key));
+    }
+
+    // Count references held by a copied complex value
+    fn share(&mut self, value: &Value) {
+        let children: Vec<&Value> = match value {
+            Value::Custom(hash_map) => hash_map.values().collect(),
+            Value::Array(values) => values.iter().collect(),
+            _ => return,
+        };
+
+        for child in children {
+            match child {
+                Value::Ptr(key) => self.data.get_mut(key).1 += 1,
+                Value::Custom(..) | Value::Array(..) => self.share(child),
+                _ => {}
+            }
+        }
+    }
+
+    // Give up an array's reference to a removed element
+    fn take_element(&mut self, element: Value) -> Value {
+        match element {
+            Value::Ptr(key) => {
+                let data = self.data.get_mut(&key);
+                data.1 -= 1;
+
+                match data.1 {
+                    0 => self.data.remove(&key).0,
+                    _ => Value::Ptr(key),
+                }
+            }
+            _ => element,
+        }
+    }
+
+    // Key of a cell owned by an array element. Unowned temporaries are moved
+    // instead of copied
+    fn adopt(&mut self, value: Value) -> usize {
+        match value {
+            Value::Ptr(key) if self.data[&key].1 == 0 => {
+                self.data.get_mut(&key).1 = 1;
+                return key;
+            }
+            _ => {
+                let value = self.reduct(&value).clone();
+                self.share(&value);
+
+                let key = self.gc.next();
+                self.data.insert(key, Cell(value, 1));
+                return key;
+            }
+        }
+    }
+
+    fn values_eq(&self, a: &Value, b: &Value) -> bool {
+        match (self.reduct(a), self.reduct(b)) {
+            (Value::Int(i1), Value::Int(i2)) => i1 == i2,
+            (Value::Float(f1), Value::Float(f2)) => f1 == f2,
+            (Value::Str(s1), Value::Str(s2)) => s1 == s2,
+            (Value::Bool(b1), Value::Bool(b2)) => b1 == b2,
+            (Value::Null, Value::Null) => true,
+            (Value::Array(a1), Value::Array(a2)) => {
+                a1.len() == a2.len() && a1.iter().zip(a2).all(|(x, y)| self.values_eq(x, y))
+            }
+            (Value::Custom(m1), Value::Custom(m2)) => m1
+                .iter()
+                .all(|(k, x)| m2.get(k).is_some_and(|y| self.values_eq(x, y))),
+            _ => false,
+        }
+    }
+
+    // Display a value the way it would be written in a program
+    fn repr(&self, value: &Value, names: &VNameConverter) -> String {
+        match self.reduct(value) {
+            Value::Str(s) => format!("{s:?}"),
+            Value::Int(i) => i.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Null => String::from(nms::NULL),
+            Value::Array(arr) => format!(
+                "[{}]",
+                arr.iter()
+                    .map(|x| self.repr(x, names))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Value::Custom(hash_map) => {
+                let mut fields = hash_map
+                    .iter()
+                    .map(|(k, x)| {
+                        format!("{}: {}", names.name(*k).unwrap_or("?"), self.repr(x, names))
+                    })
+                    .collect::<Vec<String>>();
+                fields.sort();
+
+                format!("{{{}}}", fields.join(", "))
+            }
+            Value::Ptr(..) => panic!(),
+        }
+    }
+
+    // Free a value popped off the stack that nothing else refers to
+    fn dispose(&mut self, value: Value) {
+        match value {
+            Value::Ptr(key) if self.data[&key].1 == 0 => {
+                let val = self.data.remove(&key).0;
+                self.release_complex(val, &0);
+            }
+            Value::Array(..) | Value::Custom(..) => self.release_complex(value, &0),
+            _ => {}
+        }
+    }
+
+    fn drop_ref(&mut self, key: usize) {
+        let data = self.data.get_mut(&key);
+        data.1 -= 1;
+
+        if data.1 == 0 {
+            let val = self.data.remove(&key).0;
+            if let Value::Array(..) | Value::Custom(..) = val {
+                self.release_complex(val, &0);
+            }
+        }
+    }
+
     fn release_complex(&mut self, value: Value, reserve: &usize) {
         match value {
             Value::Custom(hash_map) => {
@@ -650,6 +1638,21 @@ impl<'a> Runner<'a> {
             CMD::Push(var_address) => match var_address {
                 VarAdress::Index(val) => {
                     match self.stack_pop() {
+                        // The field of an unowned temporary is taken out of it
+                        // before the rest is freed
+                        Value::Ptr(to) if self.data[&to].1 == 0 => {
+                            let mut obj = self.data.remove(&to).0;
+                            let field = match &mut obj {
+                                Value::Custom(hash_map) => hash_map.remove(val).unwrap(),
+                                _ => panic!(),
+                            };
+                            self.release_complex(obj, &0);
+
+                            if let Value::Ptr(key) = field {
+                                self.data.get_mut(&key).1 -= 1;
+                            }
+                            self.stack.push(field);
+                        }
                         Value::Ptr(to) => match &self.data[&to].0 {
                             Value::Custom(hash_map) => self.stack.push(hash_map[val].clone()),
                             _ => panic!(),
@@ -678,9 +1681,7 @@ impl<'a> Runner<'a> {
             }
             CMD::Let(n) => {
                 let v = self.stack_pop();
-                if let Value::Ptr(to) = v {
-                    self.data.get_mut(&to).1 += 1;
-                }
+                let v = self.boxed(v);
 
                 match self.scopes.get_mut(*n) {
                     Some(lst) => lst.push(v),
@@ -702,6 +1703,10 @@ impl<'a> Runner<'a> {
             CMD::InternalOp(op, loc) => {
                 self.internal_op(op, loc)?;
                 self.current_postion += 1;
+
+                if self.exit_code.is_some() {
+                    return Ok(true);
+                }
             }
             CMD::PushLit(literal) => {
                 self.stack.push(literal.clone());
@@ -719,22 +1724,127 @@ impl<'a> Runner<'a> {
                 self.current_postion += 1;
             }
             CMD::Burn => {
-                self.stack_pop();
+                let value = self.stack_pop();
+                self.dispose(value);
                 self.current_postion += 1;
             }
             CMD::Update(reduct) => {
                 let new = self.stack_pop();
+                let new = self.boxed(new);
+
+                self.set_var(reduct, new);
+                self.current_postion += 1;
+            }
+            CMD::UpdateField(field) => {
+                let new = self.stack_pop();
+                let new = self.boxed(new);
 
-                if let Value::Ptr(ref to) = new {
-                    self.data.get_mut(to).1 += 1;
+                let old = match self.stack_pop() {
+                    Value::Ptr(to) => match &mut self.data.get_mut(&to).0 {
+                        Value::Custom(hash_map) => hash_map.insert(*field, new),
+                        _ => panic!(),
+                    },
+                    _ => panic!(),
+                };
+
+                if let Some(Value::Ptr(key)) = old {
+                    self.drop_ref(key);
+                }
+
+                self.current_postion += 1;
+            }
+            CMD::UpdateIndex(loc) => {
+                let new = self.stack_pop();
+
+                let idx = self.stack_pop();
+                let idx = *idx.int(self);
+
+                let to = match self.stack_pop() {
+                    Value::Ptr(to) => to,
+                    _ => panic!(),
+                };
+
+                let arr_len = match &self.data[&to].0 {
+                    Value::Array(arr) => arr.len() as i32,
+                    _ => panic!(),
+                };
+
+                if idx < 0 || idx + 1 > arr_len {
+                    return Err(index_out_of_range(idx, arr_len, loc));
+                }
+
+                let key = self.adopt(new);
+
+                let old = match &mut self.data.get_mut(&to).0 {
+                    Value::Array(arr) => std::mem::replace(&mut arr[idx as usize], Value::Ptr(key)),
+                    _ => panic!(),
+                };
+
+                if let Value::Ptr(key) = old {
+                    self.drop_ref(key);
                 }
 
-                self.set_var(reduct, new);
                 self.current_postion += 1;
             }
             CMD::TRelease => self.current_postion += 1,
+            CMD::PushArray(n) => {
+                let values = self.stack.split_off(self.stack.len() - n);
+                self.reserve(n + 1, 0, &FileLocation::None)?;
+                let mut arr = Vec::with_capacity(*n);
+
+                for value in values {
+                    let key = self.adopt(value);
+                    arr.push(Value::Ptr(key));
+                }
+
+                let key = self.gc.next();
+                self.data.insert(key, Cell(Value::Array(arr), 0));
+                self.stack.push(Value::Ptr(key));
+                self.current_postion += 1;
+            }
+            CMD::PushArrayFill(loc) => {
+                let fill_ref = self.stack_pop();
+                let fill = self.reduct(&fill_ref).clone();
+                let size = self.stack_pop();
+                let size = *size.int(self);
+
+                if size < 0 {
+                    return Err(RuntimeError::new(
+                        format!("Array size must not be negative. Found {size}."),
+                        loc.clone(),
+                        codes::R0004,
+                    ));
+                }
+
+                self.reserve(size as usize + 1, 0, loc)?;
+                let mut arr = Vec::with_capacity(size as usize);
+                for _ in 0..size {
+                    self.share(&fill);
+
+                    let key = self.gc.next();
+                    self.data.insert(key, Cell(fill.clone(), 1));
+                    arr.push(Value::Ptr(key));
+                }
+
+                // Unowned temporaries are released after being copied
+                if let Value::Ptr(to) = fill_ref {
+                    if self.data[&to].1 == 0 {
+                        let val = self.data.remove(&to).0;
+                        if let Value::Array(..) | Value::Custom(..) = val {
+                            self.release_complex(val, &0);
+                        }
+                    }
+                }
+
+                let key = self.gc.next();
+                self.data.insert(key, Cell(Value::Array(arr), 0));
+                self.stack.push(Value::Ptr(key));
+                self.current_postion += 1;
+            }
             CMD::PushVec => {
-                self.stack.push(Value::Array(Vec::new()));
+                let key = self.gc.next();
+                self.data.insert(key, Cell(Value::Array(Vec::new()), 0));
+                self.stack.push(Value::Ptr(key));
                 self.current_postion += 1;
             }
         }
@@ -747,10 +1857,81 @@ pub fn interpret(
     program: &FlatProgram,
     args: &Vec<String>,
     debug: bool,
-) -> Result<(), RuntimeError> {
-    let mut runner = Runner::new(program, args);
+    sandbox: Sandbox,
+) -> Result<i32, RuntimeError> {
+    let mut runner = Runner::new(program, args, &sandbox);
     match debug {
         true => debugger::Debugger::new(runner).debug(),
         false => runner.run(),
     }
 }
+
+// Interpreter state kept between REPL entries
+pub struct ReplState {
+    scopes: Vec<Vec<Value>>,
+    data: Data,
+    gc: GlobalCounter,
+}
+impl ReplState {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            data: Data::default(),
+            gc: GlobalCounter::new(),
+        }
+    }
+}
+
+pub struct ReplResult {
+    pub value: Option<String>,
+    pub exit_code: Option<i32>,
+}
+
+pub fn interpret_repl(
+    program: &FlatProgram,
+    state: &mut ReplState,
+    sandbox: &Sandbox,
+    names: &VNameConverter,
+) -> Result<ReplResult, RuntimeError> {
+    let mut runner = Runner::resume(program, state, sandbox);
+    let bound: Vec<usize> = runner.scopes.iter().map(|scope| scope.len()).collect();
+
+    let result = runner.run().map(|_| {
+        let value = match runner.stack.pop() {
+            Some(value) => {
+                let repr = match runner.reduct(&value) {
+                    Value::Null => None,
+                    _ => Some(runner.repr(&value, names)),
+                };
+
+                runner.dispose(value);
+                repr
+            }
+            None => None,
+        };
+
+        // A variable declared again replaces its old binding
+        for idx in 0..runner.scopes.len() {
+            while runner.scopes[idx].len() > 1 {
+                if let Value::Ptr(key) = runner.scopes[idx].remove(0) {
+                    runner.drop_ref(key);
+                }
+            }
+        }
+
+        ReplResult {
+            value,
+            exit_code: runner.exit_code,
+        }
+    });
+
+    // Unwind anything a failed entry left bound
+    if result.is_err() {
+        for (scope, len) in runner.scopes.iter_mut().zip(bound) {
+            scope.truncate(len);
+        }
+    }
+
+    runner.suspend(state);
+    return result;
+}
@@ -0,0 +1,169 @@
+use crate::errors::{codes, FileLocation, RuntimeError};
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+// Capabilities granted to a running program. Paths are None when the
+// capability is denied and empty when it is granted everywhere.
+pub struct Sandbox {
+    read: Option<Vec<PathBuf>>,
+    write: Option<Vec<PathBuf>>,
+    env: bool,
+    run: bool,
+    pub max_steps: Option<u64>,
+    pub max_memory: Option<usize>,
+}
+impl Sandbox {
+    pub fn new(
+        read: Option<Vec<PathBuf>>,
+        write: Option<Vec<PathBuf>>,
+        env: bool,
+        run: bool,
+        max_steps: Option<u64>,
+        max_memory: Option<usize>,
+    ) -> Self {
+        let resolve_all = |paths: Vec<PathBuf>| {
+            paths
+                .iter()
+                .map(|p| resolve(p).unwrap_or_else(|| p.to_path_buf()))
+                .collect()
+        };
+
+        Sandbox {
+            read: read.map(resolve_all),
+            write: write.map(resolve_all),
+            env,
+            run,
+            max_steps,
+            max_memory,
+        }
+    }
+
+    pub fn check_read(&self, path: &str, loc: &FileLocation) -> Result<(), RuntimeError> {
+        match path_allowed(&self.read, path) {
+            true => Ok(()),
+            false => Err(denied(&format!("Reading \"{path}\""), "--allow-read", loc)),
+        }
+    }
+
+    pub fn check_write(&self, path: &str, loc: &FileLocation) -> Result<(), RuntimeError> {
+        match path_allowed(&self.write, path) {
+            true => Ok(()),
+            false => Err(denied(&format!("Writing \"{path}\""), "--allow-write", loc)),
+        }
+    }
+
+    pub fn check_env(&self, loc: &FileLocation) -> Result<(), RuntimeError> {
+        match self.env {
+            true => Ok(()),
+            false => Err(denied("Accessing the environment", "--allow-env", loc)),
+        }
+    }
+
+    pub fn check_run(&self, program: &str, loc: &FileLocation) -> Result<(), RuntimeError> {
+        match self.run {
+            true => Ok(()),
+            false => Err(denied(
+                &format!("Running \"{program}\""),
+                "--allow-run",
+                loc,
+            )),
+        }
+    }
+
+    pub fn check_steps(&self, steps: u64) -> Result<(), RuntimeError> {
+        match self.max_steps {
+            Some(max_steps) if steps >= max_steps => Err(RuntimeError::new(
+                format!("Step limit of {max_steps} exceeded."),
+                FileLocation::None,
+                codes::R0007,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // `used` is the size in bytes the program would hold
+    pub fn check_memory(&self, used: usize, loc: &FileLocation) -> Result<(), RuntimeError> {
+        match self.max_memory {
+            Some(max_memory) if used > max_memory => Err(RuntimeError::new(
+                format!("Memory limit of {max_memory} bytes exceeded."),
+                loc.clone(),
+                codes::R0008,
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn denied(action: &str, flag: &str, loc: &FileLocation) -> RuntimeError {
+    return RuntimeError::new(
+        format!("{action} is not allowed. Run with {flag} to grant access."),
+        loc.clone(),
+        codes::R0005,
+    );
+}
+
+fn path_allowed(allowed: &Option<Vec<PathBuf>>, path: &str) -> bool {
+    match allowed {
+        None => false,
+        Some(allowed) if allowed.is_empty() => true,
+        Some(allowed) => match resolve(Path::new(path)) {
+            Some(path) => allowed.iter().any(|a| path.starts_with(a)),
+            None => false,
+        },
+    }
+}
+
+// Absolute path with symlinks and ".." resolved. None when the part of the
+// path that does not exist yet contains ".."
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let path = match env::current_dir() {
+        Ok(cwd) => cwd.join(path),
+        Err(_) => path.to_path_buf(),
+    };
+
+    // Canonicalize the longest existing prefix so ".." is applied after
+    // symlinks are followed, then append the missing tail
+    for existing in path.ancestors() {
+        if let Ok(mut resolved) = existing.canonicalize() {
+            for component in path.strip_prefix(existing).ok()?.components() {
+                match component {
+                    Component::Normal(name) => resolved.push(name),
+                    Component::CurDir => {}
+                    _ => return None,
+                }
+            }
+
+            return Some(resolved);
+        }
+    }
+
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_dir_after_symlink_stays_outside() {
+        let root = env::temp_dir().join(format!("termslang-sandbox-{}", std::process::id()));
+        let allowed = root.join("allowed");
+        let outside = root.join("outside");
+        fs::create_dir_all(&allowed).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, allowed.join("link")).unwrap();
+
+        let sandbox = Sandbox::new(None, Some(vec![allowed.clone()]), false, false, None, None);
+        let check =
+            |path: PathBuf| sandbox.check_write(path.to_str().unwrap(), &FileLocation::None);
+
+        assert!(check(allowed.join("new.txt")).is_ok());
+        assert!(check(allowed.join("link/../escaped.txt")).is_err());
+        assert!(check(allowed.join("missing/../escaped.txt")).is_err());
+        assert!(check(allowed.join("link/new.txt")).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
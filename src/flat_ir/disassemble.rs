@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use super::{FlatProgram, VNameConverter, VarAdress, CMD};
+use crate::finterpretor::Value;
+
+// Write out the tape of a flattened program, one command per line, with jump
+// targets as labels and variable names in place of their slots
+pub fn disassemble(flat_program: &FlatProgram) -> String {
+    let entries = &flat_program.function_names;
+    let start_point = flat_program.start_point;
+    let names = &flat_program.names;
+
+    // Every jump that does not go to a function gets its own label
+    let labels: HashSet<usize> = flat_program
+        .tape
+        .iter()
+        .filter_map(|cmd| match cmd {
+            CMD::Jump(idx) if !entries.contains_key(idx) => Some(*idx),
+            _ => None,
+        })
+        .collect();
+
+    let target = |idx: &usize| match entries.get(idx) {
+        Some(name) => name.clone(),
+        None => format!("L{idx}"),
+    };
+
+    let mut out = match entries.get(&start_point) {
+        Some(name) => format!("; start at {name}\n"),
+        None => String::new(),
+    };
+
+    for (idx, cmd) in flat_program.tape.iter().enumerate() {
+        if let Some(name) = entries.get(&idx) {
+            out += &format!("\n{name}:\n");
+        } else if labels.contains(&idx) {
+            out += &format!("L{idx}:\n");
+        }
+
+        let text = match cmd {
+            CMD::SplitScope => "SplitScope".to_string(),
+            CMD::Release(vars) => format!("Release {}", var_list(vars, names, ", ")),
+            CMD::TRelease => "TRelease".to_string(),
+            CMD::Defer => "Defer".to_string(),
+            CMD::Jump(idx) => format!("Jump {}", target(idx)),
+            CMD::Push(VarAdress::Var(var)) => format!("Push {}", var_name(*var, names)),
+            CMD::Push(VarAdress::Index(field)) => format!("PushField {}", var_name(*field, names)),
+            CMD::Print => "Print".to_string(),
+            CMD::PrintLn => "PrintLn".to_string(),
+            CMD::Let(var) => format!("Let {}", var_name(*var, names)),
+            CMD::Update(reduct) => format!("Update {}", var_list(reduct, names, ".")),
+            CMD::UpdateField(field) => format!("UpdateField {}", var_name(*field, names)),
+            CMD::UpdateIndex(_) => "UpdateIndex".to_string(),
+            CMD::XIf => "XIf".to_string(),
+            CMD::Refer(idx) => format!("Refer {}", target(idx)),
+            CMD::InternalOp(op, _) => format!("InternalOp {op}"),
+            CMD::PushLit(value) => format!("PushLit {}", literal(value)),
+            CMD::PushObj(fields) => format!("PushObj {}", var_list(fields, names, ", ")),
+            CMD::PushVec => "PushVec".to_string(),
+            CMD::PushArray(n) => format!("PushArray {n}"),
+            CMD::PushArrayFill(_) => "PushArrayFill".to_string(),
+            CMD::Burn => "Burn".to_string(),
+        };
+
+        out += &format!("{idx: >6}  {}\n", text.trim_end());
+    }
+
+    return out;
+}
+
+fn var_name(idx: usize, names: &VNameConverter) -> String {
+    match names.name(idx) {
+        Some(name) => name.to_string(),
+        None => format!("${idx}"),
+    }
+}
+
+fn var_list(idxs: &[usize], names: &VNameConverter, sep: &str) -> String {
+    return idxs
+        .iter()
+        .map(|idx| var_name(*idx, names))
+        .collect::<Vec<String>>()
+        .join(sep);
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Str(string) => format!("{string:?}"),
+        Value::Int(int) => int.to_string(),
+        Value::Float(float) => format!("{float:?}"),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        _ => format!("{value:?}"),
+    }
+}
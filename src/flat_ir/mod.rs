@@ -1,17 +1,21 @@
+mod disassemble;
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{
     active_parser::{
         names as nms, ACall, AFunc, AFuncBlock, ALiteral, AObject, AObjectType, AOperandExpression,
-        AOperandExpressionValue, AProgram, ATerm, ATermBlock, AType,
+        AOperandExpressionValue, AProgram, AReplEntry, ATerm, ATermBlock, AType,
     },
     errors::FileLocation,
     finterpretor::Value,
 };
+pub use disassemble::disassemble;
 
 struct ProgramBuilder {
     tape: Vec<CMD>,
     function_idxs: HashMap<u32, usize>,
+    // Name of the function starting at each index of the tape
+    function_names: HashMap<usize, String>,
     main_function: usize,
     non_indexed_refers: Vec<(usize, u32)>,
     non_indexed_loops: Vec<Vec<usize>>,
@@ -23,6 +27,7 @@ impl ProgramBuilder {
         ProgramBuilder {
             tape: Vec::new(),
             function_idxs: HashMap::new(),
+            function_names: HashMap::new(),
             main_function: 0,
             non_indexed_refers: Vec::new(),
             non_indexed_loops: Vec::new(),
@@ -102,12 +107,13 @@ pub enum VarAdress {
     Var(usize),
 }
 
+#[derive(Clone)]
 pub struct VNameConverter {
     name_map: HashMap<String, usize>,
     idx: usize,
 }
 impl VNameConverter {
-    fn new() -> VNameConverter {
+    pub fn new() -> VNameConverter {
         VNameConverter {
             idx: 0,
             name_map: HashMap::new(),
@@ -124,6 +130,13 @@ impl VNameConverter {
             }
         }
     }
+
+    pub fn name(&self, idx: usize) -> Option<&str> {
+        self.name_map
+            .iter()
+            .find(|(_, i)| **i == idx)
+            .map(|(name, _)| name.as_str())
+    }
 }
 
 #[derive(Debug)]
@@ -138,12 +151,16 @@ pub enum CMD {
     PrintLn,
     Let(usize),
     Update(Vec<usize>),
+    UpdateField(usize),
+    UpdateIndex(FileLocation),
     XIf,
     Refer(usize),
     InternalOp(String, FileLocation),
     PushLit(Value),
     PushObj(Vec<usize>),
     PushVec,
+    PushArray(usize),
+    PushArrayFill(FileLocation),
     Burn,
 }
 
@@ -151,6 +168,10 @@ pub struct FlatProgram {
     pub tape: Vec<CMD>,
     pub start_point: usize,
     pub n_scopes: usize,
+    pub root_fields: HashMap<&'static str, usize>,
+    // Names of functions and variable slots, for the disassembler
+    pub function_names: HashMap<usize, String>,
+    pub names: VNameConverter,
 }
 
 fn add_block(
@@ -208,6 +229,52 @@ fn peek_reduct(object: &AObject, reduct: &mut Vec<usize>, pb: &mut ProgramBuilde
     }
 }
 
+fn truncate_object(object: &AObject, n: usize) -> AObject {
+    AObject {
+        kind: object.kind.clone(),
+        sub: match (&object.sub, n) {
+            (Some(sub), 2..) => Some(Box::new(truncate_object(sub, n - 1))),
+            _ => None,
+        },
+        _type: object._type.clone(),
+        loc: object.loc.clone(),
+    }
+}
+
+fn add_update(pb: &mut ProgramBuilder, var: &AObject, value: &AOperandExpression) {
+    let mut chain = vec![var];
+    while let Some(sub) = &chain.last().unwrap().sub {
+        chain.push(sub);
+    }
+
+    // Plain variable and field updates
+    if !chain.iter().any(|x| matches!(x.kind, AObjectType::Call(_))) {
+        add_operand_block(pb, value);
+        let mut vec = Vec::new();
+        peek_reduct(var, &mut vec, pb);
+
+        pb.push(CMD::Update(vec));
+        return;
+    }
+
+    match &chain.last().unwrap().kind {
+        // Store through index: container[idx] = value
+        AObjectType::Call(acall) => {
+            add_object(pb, &truncate_object(var, chain.len() - 2), None);
+            add_operand_block(pb, &acall.args[0]);
+            add_operand_block(pb, value);
+            pb.push(CMD::UpdateIndex(chain.last().unwrap().loc.clone()));
+        }
+        // Store through field: container.field = value
+        AObjectType::Identity(id) => {
+            add_object(pb, &truncate_object(var, chain.len() - 1), None);
+            add_operand_block(pb, value);
+            let idx = pb.name_converter.convert(id);
+            pb.push(CMD::UpdateField(idx));
+        }
+    }
+}
+
 fn add_object(pb: &mut ProgramBuilder, object: &AObject, parent: Option<&AObject>) {
     match &object.kind {
         AObjectType::Identity(id) => {
@@ -276,6 +343,44 @@ fn add_operand_block(pb: &mut ProgramBuilder, block: &AOperandExpression) {
             add_operand_block(pb, &left);
             add_object(pb, right, None);
         }
+        AOperandExpressionValue::And { left, right } => {
+            // Only evaluate right if left is true
+            add_operand_block(pb, left);
+            pb.push(CMD::XIf);
+            let false_gt = pb.push(CMD::Jump(0));
+            add_operand_block(pb, right);
+            let end_gt = pb.push(CMD::Jump(0));
+
+            let idx = pb.push(CMD::PushLit(Value::Bool(false)));
+            if let CMD::Jump(ref mut to) = pb.tape[false_gt] {
+                *to = idx;
+            }
+
+            let idx = pb.len();
+            if let CMD::Jump(ref mut to) = pb.tape[end_gt] {
+                *to = idx;
+            }
+        }
+        AOperandExpressionValue::Or { left, right } => {
+            // Only evaluate right if left is false
+            add_operand_block(pb, left);
+            pb.push(CMD::XIf);
+            let right_gt = pb.push(CMD::Jump(0));
+            pb.push(CMD::PushLit(Value::Bool(true)));
+            let end_gt = pb.push(CMD::Jump(0));
+
+            let idx = pb.len();
+            if let CMD::Jump(ref mut to) = pb.tape[right_gt] {
+                *to = idx;
+            }
+
+            add_operand_block(pb, right);
+
+            let idx = pb.len();
+            if let CMD::Jump(ref mut to) = pb.tape[end_gt] {
+                *to = idx;
+            }
+        }
         AOperandExpressionValue::Object(aobject) => add_object(pb, aobject, None),
         AOperandExpressionValue::Literal(aliteral) => {
             let v = match aliteral {
@@ -287,10 +392,24 @@ fn add_operand_block(pb: &mut ProgramBuilder, block: &AOperandExpression) {
 
             pb.push(CMD::PushLit(v));
         }
-        AOperandExpressionValue::Create { _type, args } => match &*_type.borrow() {
-            AType::ArrayObject(..) => {
-                pb.push(CMD::PushVec);
+        AOperandExpressionValue::Array(values) => {
+            for value in values {
+                add_operand_block(pb, value);
             }
+
+            pb.push(CMD::PushArray(values.len()));
+        }
+        AOperandExpressionValue::Create { _type, args } => match &*_type.borrow() {
+            AType::ArrayObject(..) => match &args[..] {
+                [size, fill] => {
+                    add_operand_block(pb, size);
+                    add_operand_block(pb, fill);
+                    pb.push(CMD::PushArrayFill(block.loc.clone()));
+                }
+                _ => {
+                    pb.push(CMD::PushVec);
+                }
+            },
             AType::StructDefRef(_t) => match _t.root {
                 false => {
                     let fields = _t
@@ -354,13 +473,7 @@ fn add_term(
             pb.release_scope(defer_count, release_count, scopes, *defer_count, false);
             pb.push(CMD::Defer);
         }
-        ATerm::UpdateVar { value, var } => {
-            add_operand_block(pb, value);
-            let mut vec = Vec::new();
-            peek_reduct(var, &mut vec, pb);
-
-            pb.push(CMD::Update(vec));
-        }
+        ATerm::UpdateVar { value, var } => add_update(pb, var, value),
         ATerm::If {
             conditional,
             block,
@@ -400,6 +513,7 @@ fn add_term(
             counter,
             conditional,
             block,
+            ..
         } => {
             pb.split_scope(defer_count, release_count, scopes, pb.debug);
             pb.push(CMD::PushLit(Value::Int(-1)));
@@ -440,7 +554,7 @@ fn add_term(
 
             pb.release_scope(defer_count, release_count, scopes, 1, true);
         }
-        ATerm::Break => {
+        ATerm::Break(_) => {
             pb.release_scope(
                 defer_count,
                 release_count,
@@ -452,7 +566,7 @@ fn add_term(
             pb.non_indexed_loops.last_mut().unwrap().push(idx);
             pb.push(CMD::Jump(1));
         }
-        ATerm::Continue => {
+        ATerm::Continue(_) => {
             pb.release_scope(
                 defer_count,
                 release_count,
@@ -512,29 +626,86 @@ fn add_function(pb: &mut ProgramBuilder, func: &AFunc, push_this: bool, return_t
 
 pub fn flatten(program: &AProgram, debug: bool) -> FlatProgram {
     let mut pb = ProgramBuilder::new(debug);
+    add_program(&mut pb, program);
+
+    let start_point = pb.main_function;
+    return finish_program(&mut pb, start_point);
+}
+
+// Compile a REPL entry after the program. Variables declared at the top level
+// stay bound, and an expression's value is left on the stack.
+pub fn flatten_repl(
+    program: &AProgram,
+    entry: &AReplEntry,
+    name_converter: &mut VNameConverter,
+) -> FlatProgram {
+    let mut pb = ProgramBuilder::new(false);
+    pb.name_converter = std::mem::replace(name_converter, VNameConverter::new());
+    add_program(&mut pb, program);
+
+    let start_point = pb.len();
+    match entry {
+        AReplEntry::Terms(ATermBlock::A { terms }) => {
+            let mut defer_count = 0;
+            let mut scopes = Vec::new();
+            pb.split_scope(&mut defer_count, &mut Vec::new(), &mut scopes, false);
+
+            for term in terms {
+                add_term(
+                    &mut pb,
+                    term,
+                    &mut defer_count,
+                    &mut Vec::new(),
+                    &mut scopes,
+                );
+            }
+        }
+        AReplEntry::Terms(_) => panic!(),
+        AReplEntry::Expression(expression) => add_operand_block(&mut pb, expression),
+    }
+    pb.push(CMD::Defer);
+
+    let flat_program = finish_program(&mut pb, start_point);
+    *name_converter = pb.name_converter;
+    return flat_program;
+}
 
+fn add_program(pb: &mut ProgramBuilder, program: &AProgram) {
     for func in &program.functions {
-        add_function(&mut pb, func, false, false);
+        pb.function_names.insert(pb.len(), func.name.clone());
+        add_function(pb, func, false, false);
     }
 
     for _struct in &program.structs {
         for func in _struct.methods.values() {
-            add_function(&mut pb, func, true, func.name == nms::F_NEW);
+            let name = format!("{}.{}", _struct.name, func.name);
+            pb.function_names.insert(pb.len(), name);
+            add_function(pb, func, true, func.name == nms::F_NEW);
         }
     }
 
-    for (idx, func) in pb.non_indexed_refers {
-        match pb.tape.get_mut(idx) {
+    for (idx, func) in &pb.non_indexed_refers {
+        match pb.tape.get_mut(*idx) {
             Some(CMD::Refer(ref mut idx)) => {
-                *idx = pb.function_idxs[&func];
+                *idx = pb.function_idxs[func];
             }
             _ => panic!(),
         }
     }
+}
+
+fn finish_program(pb: &mut ProgramBuilder, start_point: usize) -> FlatProgram {
+    let root_fields = nms::ROOT_FIELDS
+        .into_iter()
+        .map(|field| (field, pb.name_converter.convert(field)))
+        .collect();
 
     return FlatProgram {
-        tape: pb.tape,
-        start_point: pb.main_function,
+        root_fields,
+        tape: std::mem::take(&mut pb.tape),
+        start_point,
         n_scopes: pb.name_converter.idx + 1,
+        function_names: std::mem::take(&mut pb.function_names),
+        names: pb.name_converter.clone(),
     };
 }
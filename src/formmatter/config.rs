@@ -0,0 +1,156 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::errors::{codes, source_map, suggest, FileLocation, ManagerError};
+
+// File in a program's directory, or one above it, that sets the format style
+const CONFIG_FILE: &str = "termsfmt.toml";
+
+const SETTINGS: [&str; 3] = ["indent_width", "max_width", "brace_style"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BraceStyle {
+    // `if x {`
+    SameLine,
+    // `if x` with `{` on the line below
+    NextLine,
+}
+impl BraceStyle {
+    const ALL: [BraceStyle; 2] = [BraceStyle::SameLine, BraceStyle::NextLine];
+
+    fn name(&self) -> &'static str {
+        match self {
+            BraceStyle::SameLine => return "same_line",
+            BraceStyle::NextLine => return "next_line",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Style {
+    pub indent_width: usize,
+    // Longest a line can be before call arguments are put on lines of their own
+    pub max_width: usize,
+    pub brace_style: BraceStyle,
+}
+impl Style {
+    pub fn new() -> Self {
+        Style {
+            indent_width: 4,
+            max_width: 100,
+            brace_style: BraceStyle::SameLine,
+        }
+    }
+
+    // Apply a line such as `indent_width = 2`
+    fn apply(&mut self, line: &str, loc: &FileLocation) -> Result<(), ManagerError> {
+        let invalid = |msg: String| ManagerError::new(msg, loc.clone(), codes::M0007);
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => return Err(invalid(format!("Expected key = value, found `{line}`."))),
+        };
+
+        match key {
+            "indent_width" | "max_width" => {
+                let number = match value.parse::<usize>() {
+                    Ok(number) if number > 0 => number,
+                    _ => {
+                        return Err(invalid(format!(
+                            "Expected a positive whole number for {key}, found {value}."
+                        )))
+                    }
+                };
+
+                match key {
+                    "indent_width" => self.indent_width = number,
+                    _ => self.max_width = number,
+                }
+            }
+            "brace_style" => {
+                let name = value.trim_matches('"');
+                self.brace_style = match BraceStyle::ALL.into_iter().find(|s| s.name() == name) {
+                    Some(brace_style) => brace_style,
+                    None => {
+                        return Err(invalid(format!(
+                            "Unknown brace style {value}.{}",
+                            suggest::did_you_mean(name, BraceStyle::ALL.map(|s| s.name()))
+                        )))
+                    }
+                };
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "Unknown format setting {key}.{}",
+                    suggest::did_you_mean(key, SETTINGS)
+                )))
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+// Style for a program file, from the nearest config file
+pub fn file_style(path: &Path) -> Result<Style, ManagerError> {
+    let mut style = Style::new();
+
+    let config = match find_config(path) {
+        Some(config) => config,
+        None => return Ok(style),
+    };
+
+    let text = match fs::read_to_string(&config) {
+        Ok(text) => text,
+        Err(err) => {
+            return Err(ManagerError::new(
+                format!("Could not open format config {} | {err}", config.display()),
+                FileLocation::None,
+                codes::M0001,
+            ))
+        }
+    };
+
+    // Keep the config in the source map so errors can point into it
+    let source = source_map::add_source(config.clone(), text.clone());
+
+    for (line_idx, line) in text.lines().enumerate() {
+        // The config is a flat TOML file, so a comment can follow a value
+        let line = match line.split_once('#') {
+            Some((line, _)) => line,
+            None => line,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let loc = FileLocation::Loc {
+            file: source,
+            start_line: line_idx,
+            end_line: line_idx,
+            start_col: 1,
+            end_col: line.trim_end().chars().count(),
+        };
+        style.apply(line.trim(), &loc)?;
+    }
+
+    return Ok(style);
+}
+
+fn find_config(path: &Path) -> Option<PathBuf> {
+    let path = match fs::canonicalize(path) {
+        Ok(path) => path,
+        Err(_) => path.to_path_buf(),
+    };
+
+    for dir in path.ancestors().skip(1) {
+        let config = dir.join(CONFIG_FILE);
+        if config.is_file() {
+            return Some(config);
+        }
+    }
+
+    return None;
+}
@@ -0,0 +1,120 @@
+use colored::Colorize;
+
+// Lines of context shown around each change
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+// Edits turning one list of lines into another, from their longest common
+// subsequence
+pub fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = match old[i] == new[j] {
+                true => lengths[i + 1][j + 1] + 1,
+                false => lengths[i + 1][j].max(lengths[i][j + 1]),
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push(Edit::Remove);
+            i += 1;
+        } else {
+            edits.push(Edit::Add);
+            j += 1;
+        }
+    }
+
+    return edits;
+}
+
+// Unified diff between two versions of a file
+pub fn unified(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = edits(&old_lines, &new_lines);
+
+    let mut diff = format!(
+        "{}\n{}\n",
+        format!("--- {path}").bold(),
+        format!("+++ {path}").bold()
+    );
+
+    // Position in each file at the start of every edit
+    let mut positions = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for edit in &edits {
+        positions.push((i, j));
+        match edit {
+            Edit::Keep => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Remove => i += 1,
+            Edit::Add => j += 1,
+        }
+    }
+
+    let mut idx = 0;
+    while idx < edits.len() {
+        if edits[idx] == Edit::Keep {
+            idx += 1;
+            continue;
+        }
+
+        // Grow the hunk while changes are close enough to share context
+        let start = idx.saturating_sub(CONTEXT);
+        let mut end = idx;
+        let mut kept = 0;
+        while end < edits.len() && kept <= CONTEXT * 2 {
+            match edits[end] {
+                Edit::Keep => kept += 1,
+                _ => kept = 0,
+            }
+            end += 1;
+        }
+        end -= kept.saturating_sub(CONTEXT);
+
+        let (old_start, new_start) = positions[start];
+        let old_len = edits[start..end]
+            .iter()
+            .filter(|e| **e != Edit::Add)
+            .count();
+        let new_len = edits[start..end]
+            .iter()
+            .filter(|e| **e != Edit::Remove)
+            .count();
+        let header = format!(
+            "@@ -{},{old_len} +{},{new_len} @@",
+            old_start + 1,
+            new_start + 1
+        );
+        diff += &format!("{}\n", header.cyan());
+
+        for (edit, (i, j)) in edits[start..end].iter().zip(&positions[start..end]) {
+            match edit {
+                Edit::Keep => diff += &format!(" {}\n", old_lines[*i]),
+                Edit::Remove => diff += &format!("{}\n", format!("-{}", old_lines[*i]).red()),
+                Edit::Add => diff += &format!("{}\n", format!("+{}", new_lines[*j]).green()),
+            }
+        }
+
+        idx = end;
+    }
+
+    return diff;
+}
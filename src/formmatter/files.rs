@@ -0,0 +1,185 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+
+use crate::{
+    check,
+    errors::{self, codes, source_map, ErrorType, FileLocation, ManagerError},
+    formmatter::{config, diff},
+};
+
+// Name errors and diffs use for a program read from standard input
+const STDIN_NAME: &str = "<stdin>";
+
+// What to do with a formatted file
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    Write,
+    Stdout,
+    // Report files that are not formatted without changing them
+    Check,
+}
+
+struct Run {
+    mode: Mode,
+    // Status of the earliest stage that failed in any file
+    status: i32,
+    n_files: usize,
+    n_unformatted: usize,
+}
+impl Run {
+    fn error(&mut self, msg: String, code: i32) {
+        eprintln!("{msg}");
+        if self.status == 0 || code < self.status {
+            self.status = code;
+        }
+    }
+
+    // Handle a formatted file, which is written back when it has a path
+    fn output(&mut self, name: &str, file: Option<&PathBuf>, program: &str, text: &str) {
+        self.n_files += 1;
+
+        match (self.mode, file) {
+            (Mode::Check, _) => {
+                if program != text {
+                    print!("{}", diff::unified(name, program, text));
+                    self.n_unformatted += 1;
+                }
+            }
+            (Mode::Write, Some(file)) => {
+                if program == text {
+                    return;
+                }
+
+                if let Err(err) = fs::write(file, text) {
+                    let err = ManagerError::new(
+                        format!("Could not write to program file {name} | {err}"),
+                        FileLocation::None,
+                        codes::M0002,
+                    );
+                    self.error(err.report(), errors::EXIT_MANAGER);
+                }
+            }
+            (Mode::Write | Mode::Stdout, _) => print!("{text}"),
+        }
+    }
+}
+
+// Format files, searching directories for program files, or standard input
+// when no paths are given. Returns the exit status.
+pub fn format_paths(paths: &[PathBuf], mode: Mode) -> i32 {
+    let mut run = Run {
+        mode,
+        status: 0,
+        n_files: 0,
+        n_unformatted: 0,
+    };
+
+    if paths.is_empty() {
+        // Look for a config from the working directory
+        let config_path = match std::env::current_dir() {
+            Ok(dir) => dir.join(STDIN_NAME),
+            Err(_) => PathBuf::from(STDIN_NAME),
+        };
+
+        let mut program = String::new();
+        match io::stdin().read_to_string(&mut program) {
+            Ok(_) => match format_text(program, Path::new(STDIN_NAME), &config_path) {
+                Ok((program, text)) => run.output(STDIN_NAME, None, &program, &text),
+                Err((msg, code)) => run.error(msg, code),
+            },
+            Err(err) => {
+                let err = ManagerError::new(
+                    format!("Could not read standard input | {err}"),
+                    FileLocation::None,
+                    codes::M0001,
+                );
+                run.error(err.report(), errors::EXIT_MANAGER);
+            }
+        }
+    } else {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                if let Err(err) = check::collect_files(path, &mut files) {
+                    run.error(err.report(), errors::EXIT_MANAGER);
+                }
+            } else {
+                files.push(path.clone());
+            }
+        }
+
+        for file in &files {
+            match format_file(file) {
+                Ok((program, text)) => {
+                    let name = file.display().to_string();
+                    run.output(&name, Some(file), &program, &text);
+                }
+                Err((msg, code)) => run.error(msg, code),
+            }
+        }
+    }
+
+    if mode == Mode::Check {
+        let summary = format!(
+            "Checked {} file{}: {} not formatted",
+            run.n_files,
+            if run.n_files == 1 { "" } else { "s" },
+            run.n_unformatted
+        );
+
+        // Keep the summary apart from the diffs on stdout
+        match (errors::json_messages(), run.n_unformatted, run.status) {
+            (true, ..) => eprintln!("{summary}"),
+            (false, 0, 0) => eprintln!("{}", summary.green()),
+            (false, _, 0) => eprintln!("{}", summary.yellow()),
+            (false, ..) => eprintln!("{}", summary.red()),
+        }
+    }
+
+    if run.status == 0 && run.n_unformatted > 0 {
+        return errors::EXIT_FORMAT;
+    }
+
+    return run.status;
+}
+
+// Read and format a file, giving its text before and after
+fn format_file(file: &PathBuf) -> Result<(String, String), (String, i32)> {
+    let program = match fs::read_to_string(file) {
+        Ok(program) => program,
+        Err(err) => {
+            let err = ManagerError::new(
+                format!("Could not open program file {} | {err}", file.display()),
+                FileLocation::None,
+                codes::M0001,
+            );
+            return Err((err.report(), errors::EXIT_MANAGER));
+        }
+    };
+
+    return format_text(program, file, file);
+}
+
+fn format_text(
+    program: String,
+    path: &Path,
+    config_path: &Path,
+) -> Result<(String, String), (String, i32)> {
+    let style = match config::file_style(config_path) {
+        Ok(style) => style,
+        Err(err) => return Err((err.report(), errors::EXIT_MANAGER)),
+    };
+
+    let source = source_map::add_source(path.to_path_buf(), program.clone());
+    match super::format(source, &style) {
+        Ok(text) => return Ok((program, text)),
+        Err(ErrorType::Lexer(err)) => return Err((err.report(), errors::EXIT_LEXER)),
+        Err(ErrorType::Parser(err)) => return Err((err.report(), errors::EXIT_PARSER)),
+        Err(_) => panic!(),
+    }
+}
@@ -0,0 +1,924 @@
+pub mod config;
+pub mod diff;
+pub mod files;
+
+use crate::{
+    errors::{source_map, ErrorType, FileLocation, SourceId},
+    formmatter::config::{BraceStyle, Style},
+    lexer::{
+        self,
+        syntax::{self, get_syntax_map, SyntaxMap},
+        tokens::{KeyWord, Operator, Token, TokenType},
+    },
+    parser::{
+        self,
+        parse_operand_block::{get_precedent_map, OperandExpression, OperandExpressionValue},
+        Function, Import, Object, ObjectType, Program, Struct, Term, TermBlock, Type, VarSigniture,
+    },
+};
+
+// Rendered code, as lines with their depth past the indent they are written at
+type Lines = Vec<(usize, String)>;
+
+// Position of a token in the source: (line, col)
+type Pos = (usize, usize);
+
+struct Comment {
+    text: String,
+    pos: Pos,
+}
+
+enum Item<'a> {
+    Import(&'a Import),
+    Struct(&'a Struct),
+    Function(&'a Function),
+}
+
+enum Member<'a> {
+    Property(&'a VarSigniture),
+    Method(&'a Function),
+}
+
+struct Formatter<'a> {
+    style: &'a Style,
+    syntax_map: SyntaxMap<'static>,
+    precedence: Vec<Vec<Operator>>,
+    // Tokens of the program without its comments
+    tokens: Vec<Token>,
+    comments: Vec<Comment>,
+    // Comments inside the arguments of a call or array, with the position
+    // of the bracket opening them. They are written with the arguments.
+    list_comments: Vec<(Pos, Comment)>,
+    next_comment: usize,
+    lines: Vec<String>,
+    indent: usize,
+    // Last source line written, used to keep the blank lines between terms
+    last_line: usize,
+    // Whether the last line written opened a block
+    opened: bool,
+    // Whether the next line written must follow a blank line
+    blank: bool,
+}
+impl<'a> Formatter<'a> {
+    fn write(&mut self, lines: Lines) {
+        for (depth, text) in lines {
+            let indent = " ".repeat(self.style.indent_width * (self.indent + depth));
+            self.lines.push(format!("{indent}{text}"));
+        }
+
+        self.opened = false;
+    }
+
+    // Keep a blank line from the source, or a required one, before a line
+    fn gap(&mut self, line: usize) {
+        if !self.opened && !self.lines.is_empty() && (self.blank || line > self.last_line + 1) {
+            self.lines.push(String::new());
+        }
+
+        self.blank = false;
+    }
+
+    // Write the comments before a position on lines of their own
+    fn leading(&mut self, pos: Pos) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.pos >= pos {
+                break;
+            }
+
+            let line = comment.pos.0;
+            let text = format!("#{}", comment.text.trim_end());
+            self.next_comment += 1;
+
+            self.gap(line);
+            self.write(vec![(0, text)]);
+            self.last_line = line;
+        }
+    }
+
+    // Write the comments up to the end of the source line a written token
+    // ends on, and before any code following it. A lone comment goes at the
+    // end of the line written.
+    fn trailing(&mut self, end: usize) {
+        let line = match &self.tokens[end] {
+            Token(TokenType::String(_), loc) => end_line(loc),
+            _ => self.line_of(end),
+        };
+        let next = match self.tokens.get(end + 1) {
+            Some(token) => pos(&token.1),
+            None => (usize::MAX, 0),
+        };
+
+        let start = self.next_comment;
+        let end = match self.comments[start..]
+            .iter()
+            .position(|comment| comment.pos.0 > line || comment.pos >= next)
+        {
+            Some(n) => start + n,
+            None => self.comments.len(),
+        };
+
+        if end - start == 1 {
+            let text = format!(" #{}", self.comments[start].text.trim_end());
+            self.lines.last_mut().unwrap().push_str(&text);
+        } else {
+            let lines = self.comments[start..end]
+                .iter()
+                .map(|comment| (0, format!("#{}", comment.text.trim_end())))
+                .collect();
+            self.write(lines);
+        }
+
+        self.next_comment = end;
+        self.last_line = self.last_line.max(line);
+    }
+
+    // Index of the first token of a type at or after a position
+    fn find(&self, from: Pos, token_type: TokenType) -> usize {
+        let start = self.tokens.partition_point(|token| pos(&token.1) < from);
+        for (idx, token) in self.tokens.iter().enumerate().skip(start) {
+            if token.0 == token_type {
+                return idx;
+            }
+        }
+
+        panic!();
+    }
+
+    fn matching_close(&self, open: usize) -> usize {
+        let mut depth = 0;
+        for (idx, token) in self.tokens.iter().enumerate().skip(open) {
+            match token.0 {
+                TokenType::Operator(Operator::OpenBlock) => depth += 1,
+                TokenType::Operator(Operator::CloseBlock) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return idx;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        panic!();
+    }
+
+    // Whether an if is followed by an else block holding only comments
+    fn commented_else(&self, close: usize) -> bool {
+        match self.tokens.get(close + 1) {
+            Some(Token(TokenType::KeyWord(KeyWord::Else), _)) => {}
+            _ => return false,
+        }
+
+        let else_close = self.matching_close(close + 2);
+        return match self.comments.get(self.next_comment) {
+            Some(comment) => comment.pos < pos(&self.tokens[else_close].1),
+            None => false,
+        };
+    }
+
+    fn line_of(&self, idx: usize) -> usize {
+        return pos(&self.tokens[idx].1).0;
+    }
+
+    // Start the body of a block whose header has just been written
+    fn open(&mut self, open: usize) {
+        self.opened = true;
+        self.indent += 1;
+        self.trailing(open);
+    }
+
+    // End the body of a block before its closing brace is written
+    fn close(&mut self, close: usize) {
+        self.leading(pos(&self.tokens[close].1));
+        self.indent -= 1;
+        self.last_line = self.line_of(close);
+    }
+
+    fn close_line(&mut self, close: usize) {
+        self.write(vec![(0, "}".to_string())]);
+        self.trailing(close);
+    }
+
+    fn program(&mut self, program: &Program, source: SourceId) {
+        let prelude = match &self.tokens[0] {
+            Token(TokenType::String(prelude), loc) => (string(prelude), loc.clone()),
+            _ => panic!(),
+        };
+        self.leading(pos(&prelude.1));
+        self.write(vec![(0, prelude.0)]);
+        self.last_line = end_line(&prelude.1);
+        self.trailing(0);
+
+        // Imported files add their structs and functions to the program
+        let in_source = |loc: &FileLocation| match loc {
+            FileLocation::Loc { file, .. } => *file == source,
+            _ => false,
+        };
+
+        let mut items = Vec::new();
+        items.extend(program.imports.iter().map(Item::Import));
+        items.extend(
+            program
+                .structs
+                .iter()
+                .filter(|astruct| in_source(&astruct.loc))
+                .map(Item::Struct),
+        );
+        items.extend(
+            program
+                .functions
+                .iter()
+                .filter(|func| in_source(&func.loc))
+                .map(Item::Function),
+        );
+        items.sort_by_key(item_pos);
+
+        // Items get a blank line between them, except imports following imports
+        let mut after_import = false;
+        for item in items {
+            let start = item_pos(&item);
+            let import = matches!(item, Item::Import(_));
+            self.blank = !(import && after_import);
+            after_import = import;
+
+            self.leading(start);
+            self.gap(start.0);
+
+            match item {
+                Item::Import(import) => self.import(import, start),
+                Item::Struct(astruct) => self.structure(astruct),
+                Item::Function(func) => self.function(func),
+            }
+        }
+
+        self.leading((usize::MAX, 0));
+    }
+
+    fn import(&mut self, import: &Import, start: Pos) {
+        let end = self.find(start, TokenType::Terminate);
+        let objects: Vec<&str> = import.objects.iter().map(|obj| obj.0.as_str()).collect();
+        self.write(vec![(
+            0,
+            format!(
+                "import {} of {} ~",
+                objects.join(", "),
+                string(&import.path)
+            ),
+        )]);
+        self.last_line = self.line_of(end);
+        self.trailing(end);
+    }
+
+    fn structure(&mut self, astruct: &Struct) {
+        let open = self.find(pos(&astruct.loc), TokenType::Operator(Operator::OpenBlock));
+        let close = self.matching_close(open);
+        self.header(vec![(
+            0,
+            format!("struct {}{}", astruct.name, self.brace()),
+        )]);
+        self.open(open);
+
+        let mut members = Vec::new();
+        members.extend(astruct.properties.iter().map(Member::Property));
+        members.extend(astruct.methods.iter().map(Member::Method));
+        members.sort_by_key(member_pos);
+
+        for member in members {
+            let start = member_pos(&member);
+            self.leading(start);
+            self.gap(start.0);
+
+            match member {
+                Member::Property(property) => {
+                    let end = self.find(start, TokenType::Terminate);
+                    self.write(vec![(0, format!("let {} ~", self.var_sig(property)))]);
+                    self.last_line = self.line_of(end);
+                    self.trailing(end);
+                }
+                Member::Method(method) => self.function(method),
+            }
+        }
+
+        self.close(close);
+        self.close_line(close);
+    }
+
+    fn function(&mut self, func: &Function) {
+        let open = self.find(pos(&func.loc), TokenType::Operator(Operator::OpenBlock));
+
+        let mut header = format!("func {} {}", self._type(&func.returntype), func.name);
+        if !func.args.is_empty() {
+            let args: Vec<String> = func.args.iter().map(|arg| self.var_sig(arg)).collect();
+            header.push_str(&format!(": {}", args.join(", ")));
+        }
+        header.push_str(self.brace());
+        self.header(vec![(0, header)]);
+
+        let close = self.block(&func.block, open);
+        self.close_line(close);
+    }
+
+    // Write the body of a block whose header has just been written. Returns
+    // the index of its closing brace, which is left to the caller.
+    fn block(&mut self, block: &TermBlock, open: usize) -> usize {
+        let close = self.matching_close(open);
+        self.open(open);
+
+        for term in &block.terms {
+            self.term(term);
+        }
+
+        self.close(close);
+        return close;
+    }
+
+    fn term(&mut self, term: &Term) {
+        let start = term_pos(term);
+        self.leading(start);
+        self.gap(start.0);
+
+        match term {
+            Term::Print { ln, operand_block } => {
+                let keyword = if *ln { "println " } else { "print " };
+                self.statement(start, keyword.to_string(), operand_block);
+            }
+            Term::DeclareVar {
+                name,
+                vartype,
+                value,
+                ..
+            } => {
+                let before = format!("let {} {name} = ", self._type(vartype));
+                self.statement(start, before, value);
+            }
+            Term::Return { value } => self.statement(start, "return ".to_string(), value),
+            Term::UpdateVar {
+                var,
+                set_operator,
+                value,
+            } => {
+                let before = format!(
+                    "updt {} {} ",
+                    self.flat(|f, lines| f.object(lines, var, None, false)),
+                    self.operator(set_operator)
+                );
+                self.statement(start, before, value);
+            }
+            Term::Call { value } => self.statement(start, "cll ".to_string(), value),
+            Term::Break(loc) => self.keyword("break", loc),
+            Term::Continue(loc) => self.keyword("continue", loc),
+            Term::If {
+                conditional,
+                block,
+                else_block,
+            } => self.if_chain(conditional, block, else_block, "if "),
+            Term::Loop {
+                counter,
+                conditional,
+                block,
+                ..
+            } => {
+                let open = self.find(
+                    expression_pos(conditional),
+                    TokenType::Operator(Operator::OpenBlock),
+                );
+                let lines =
+                    self.expression_lines(format!("loop {counter}: "), conditional, self.brace());
+                self.header(lines);
+
+                let close = self.block(block, open);
+                self.close_line(close);
+            }
+        }
+    }
+
+    // Write a term that ends with a line terminator
+    fn statement(&mut self, start: Pos, before: String, value: &OperandExpression) {
+        let end = self.find(start, TokenType::Terminate);
+        let lines = self.expression_lines(before, value, " ~");
+        self.write(lines);
+        self.last_line = self.line_of(end);
+        self.trailing(end);
+    }
+
+    // Write a term that is only a keyword and its line terminator
+    fn keyword(&mut self, keyword: &str, terminator: &FileLocation) {
+        let end = self.find(pos(terminator), TokenType::Terminate);
+        self.write(vec![(0, format!("{keyword} ~"))]);
+        self.last_line = self.line_of(end);
+        self.trailing(end);
+    }
+
+    // Text after a header that opens a block on the same line
+    fn brace(&self) -> &'static str {
+        match self.style.brace_style {
+            BraceStyle::SameLine => return " {",
+            BraceStyle::NextLine => return "",
+        }
+    }
+
+    // Write the header of a block, ending with the text from brace, and
+    // the line its opening brace is on
+    fn header(&mut self, mut lines: Lines) {
+        if self.style.brace_style == BraceStyle::NextLine {
+            lines.push((0, "{".to_string()));
+        }
+
+        self.write(lines);
+    }
+
+    // Write what comes before an else, after the block it follows
+    fn else_prefix(&mut self) -> &'static str {
+        match self.style.brace_style {
+            BraceStyle::SameLine => return "} else ",
+            BraceStyle::NextLine => {
+                self.write(vec![(0, "}".to_string())]);
+                return "else ";
+            }
+        }
+    }
+
+    // Write an if block, with an else block holding only an if written as
+    // else if
+    fn if_chain(
+        &mut self,
+        conditional: &OperandExpression,
+        block: &TermBlock,
+        else_block: &TermBlock,
+        before: &str,
+    ) {
+        let open = self.find(
+            expression_pos(conditional),
+            TokenType::Operator(Operator::OpenBlock),
+        );
+        let lines = self.expression_lines(before.to_string(), conditional, self.brace());
+        self.header(lines);
+        let close = self.block(block, open);
+
+        match else_block.terms.as_slice() {
+            [] if !self.commented_else(close) => self.close_line(close),
+            [Term::If {
+                conditional,
+                block,
+                else_block,
+            }] => {
+                let prefix = self.else_prefix();
+                self.if_chain(conditional, block, else_block, &format!("{prefix}if "))
+            }
+            _ => {
+                let open = self.find(
+                    pos(&self.tokens[close].1),
+                    TokenType::Operator(Operator::OpenBlock),
+                );
+                let prefix = self.else_prefix();
+                self.header(vec![(0, format!("{}{}", prefix.trim_end(), self.brace()))]);
+                let close = self.block(else_block, open);
+                self.close_line(close);
+            }
+        }
+    }
+
+    // Render an expression between two pieces of text, putting call
+    // arguments on lines of their own when it does not fit on one line
+    fn expression_lines(
+        &self,
+        before: String,
+        expression: &OperandExpression,
+        after: &str,
+    ) -> Lines {
+        for wrap in [false, true] {
+            let mut lines = vec![(0, before.clone())];
+            self.expression(&mut lines, expression, wrap);
+            lines.last_mut().unwrap().1.push_str(after);
+
+            if wrap || self.fits(&lines) {
+                return lines;
+            }
+        }
+
+        panic!();
+    }
+
+    fn fits(&self, lines: &Lines) -> bool {
+        return lines.iter().all(|(depth, text)| {
+            let width = text.split('\n').map(|line| line.chars().count()).max();
+            return self.style.indent_width * (self.indent + depth) + width.unwrap()
+                <= self.style.max_width;
+        });
+    }
+
+    fn flat(&self, render: impl Fn(&Self, &mut Lines)) -> String {
+        let mut lines = vec![(0, String::new())];
+        render(self, &mut lines);
+        return lines.pop().unwrap().1;
+    }
+
+    fn operator(&self, operator: &Operator) -> &'static str {
+        for (text, op) in &self.syntax_map.operators {
+            if op == operator {
+                return text;
+            }
+        }
+
+        panic!();
+    }
+
+    // Precedence of the operator an expression is split on. Higher binds
+    // tighter, and values bind tightest of all.
+    fn level(&self, expression: &OperandExpression) -> usize {
+        let operator = match &expression.0 {
+            OperandExpressionValue::Unary { operand, .. }
+            | OperandExpressionValue::Binary { operand, .. } => match &operand.0 {
+                TokenType::Operator(operator) => operator,
+                _ => panic!(),
+            },
+            OperandExpressionValue::Dot { .. } => &Operator::Dot,
+            _ => return self.precedence.len(),
+        };
+
+        return self
+            .precedence
+            .iter()
+            .position(|layer| layer.contains(operator))
+            .unwrap();
+    }
+
+    fn expression(&self, lines: &mut Lines, expression: &OperandExpression, wrap: bool) {
+        let level = self.level(expression);
+
+        match &expression.0 {
+            OperandExpressionValue::Unary { operand, val } => {
+                push(lines, self.operator(&operator_of(operand)));
+                self.operand(lines, val, self.level(val) < level, wrap);
+            }
+            OperandExpressionValue::Binary {
+                operand,
+                left,
+                right,
+            } => {
+                // Operators split on the first of the loosest operators, so
+                // only the left side needs parentheses at the same level
+                self.operand(lines, left, self.level(left) <= level, wrap);
+                push(
+                    lines,
+                    &format!(" {} ", self.operator(&operator_of(operand))),
+                );
+                self.operand(lines, right, self.level(right) < level, wrap);
+            }
+            OperandExpressionValue::Dot { left, right } => {
+                self.operand(lines, left, true, wrap);
+                push(lines, ".");
+                self.object(lines, right, None, wrap);
+            }
+            OperandExpressionValue::Literal(token) => push(lines, &literal(token)),
+            OperandExpressionValue::Object(object) => self.object(lines, object, None, wrap),
+            OperandExpressionValue::Create(create) => {
+                let open = self.find(pos(&expression.1), TokenType::Operator(Operator::OpenParen));
+                push(lines, self.operator(&Operator::New));
+                let open = pos(&self.tokens[open].1);
+                self.list(lines, ("(", open), &create.args.args, ")", wrap);
+                push(lines, &format!(" {}", self._type(&create.kind)));
+            }
+            OperandExpressionValue::Array(values) => {
+                self.list(lines, ("[", pos(&expression.1)), values, "]", wrap)
+            }
+        }
+    }
+
+    fn operand(&self, lines: &mut Lines, expression: &OperandExpression, paren: bool, wrap: bool) {
+        if paren {
+            push(lines, "(");
+        }
+
+        self.expression(lines, expression, wrap);
+
+        if paren {
+            push(lines, ")");
+        }
+    }
+
+    // Render an object following the part of an object it is a sub of
+    fn object(&self, lines: &mut Lines, object: &Object, parent: Option<&ObjectType>, wrap: bool) {
+        match &object.kind {
+            ObjectType::Identity(id) => {
+                if parent.is_some() {
+                    push(lines, ".");
+                }
+                push(lines, id);
+            }
+            ObjectType::Call(call) => {
+                if parent.is_some() {
+                    push(lines, ".");
+                }
+                self.list(lines, ("(", pos(&object.loc)), &call.args, ")", wrap);
+            }
+            ObjectType::Index(index) => {
+                // Only names and indexes can be indexed without a dot
+                if let Some(ObjectType::Call(_)) = parent {
+                    push(lines, ".");
+                }
+                push(lines, "[");
+                self.expression(lines, index, wrap);
+                push(lines, "]");
+            }
+        }
+
+        if let Some(sub) = &object.sub {
+            self.object(lines, sub, Some(&object.kind), wrap);
+        }
+    }
+
+    // Render values separated by commas, each on a line of its own when
+    // wrapping. Lists holding comments are always wrapped so the comments
+    // stay next to the values they follow.
+    fn list(
+        &self,
+        lines: &mut Lines,
+        (open, open_pos): (&str, Pos),
+        values: &[OperandExpression],
+        close: &str,
+        wrap: bool,
+    ) {
+        push(lines, open);
+
+        let comments: Vec<&Comment> = self
+            .list_comments
+            .iter()
+            .filter(|(at, _)| *at == open_pos)
+            .map(|(_, comment)| comment)
+            .collect();
+
+        if (!wrap || values.is_empty()) && comments.is_empty() {
+            for (idx, value) in values.iter().enumerate() {
+                if idx > 0 {
+                    push(lines, ", ");
+                }
+                self.expression(lines, value, false);
+            }
+
+            push(lines, close);
+            return;
+        }
+
+        let depth = lines.last().unwrap().0;
+        let mut next_comment = 0;
+        for (idx, value) in values.iter().enumerate() {
+            let separator = if idx + 1 < values.len() { "," } else { "" };
+
+            // Comments on lines of their own before the value
+            let start = expression_pos(value);
+            while let Some(comment) = comments.get(next_comment) {
+                if comment.pos >= start {
+                    break;
+                }
+                lines.push((depth + 1, format!("#{}", comment.text.trim_end())));
+                next_comment += 1;
+            }
+
+            let mut value_lines = Vec::new();
+            for wrap in [false, true] {
+                value_lines = vec![(depth + 1, String::new())];
+                self.expression(&mut value_lines, value, wrap);
+                push(&mut value_lines, separator);
+
+                if self.fits(&value_lines) {
+                    break;
+                }
+            }
+
+            lines.extend(value_lines);
+
+            // A comment on the line the value ends on stays at its end
+            let end = match values.get(idx + 1) {
+                Some(next) => expression_pos(next),
+                None => (usize::MAX, 0),
+            };
+            if let Some(comment) = comments.get(next_comment) {
+                if comment.pos < end && comment.pos.0 == self.line_before(comment.pos) {
+                    push(lines, &format!(" #{}", comment.text.trim_end()));
+                    next_comment += 1;
+                }
+            }
+        }
+
+        for comment in &comments[next_comment..] {
+            lines.push((depth + 1, format!("#{}", comment.text.trim_end())));
+        }
+
+        lines.push((depth, close.to_string()));
+    }
+
+    // Line the code before a position ends on
+    fn line_before(&self, at: Pos) -> usize {
+        let idx = self.tokens.partition_point(|token| pos(&token.1) < at);
+        return end_line(&self.tokens[idx - 1].1);
+    }
+
+    fn _type(&self, _type: &Type) -> String {
+        match _type {
+            Type::Array { _type, .. } => return format!("{}[]", self._type(_type)),
+            Type::Object { object } => {
+                return self.flat(|f, lines| f.object(lines, object, None, false))
+            }
+        }
+    }
+
+    fn var_sig(&self, var_sig: &VarSigniture) -> String {
+        return format!("{} {}", self._type(&var_sig.argtype), var_sig.identity);
+    }
+}
+
+fn push(lines: &mut Lines, text: &str) {
+    lines.last_mut().unwrap().1.push_str(text);
+}
+
+fn operator_of(token: &Token) -> Operator {
+    match &token.0 {
+        TokenType::Operator(operator) => return operator.clone(),
+        _ => panic!(),
+    }
+}
+
+// Quote a string with a quote it does not contain, as strings have no escapes
+fn string(text: &str) -> String {
+    let quote = syntax::STRING_QUOTES
+        .chars()
+        .find(|quote| !text.contains(*quote))
+        .unwrap();
+
+    return format!("{quote}{text}{quote}");
+}
+
+fn literal(token: &Token) -> String {
+    match &token.0 {
+        TokenType::Int(int) => return int.to_string(),
+        TokenType::Float(float) => {
+            // Keep the decimal point so the value stays a float
+            let text = float.to_string();
+            match text.contains(syntax::DECIMAL) {
+                true => return text,
+                false => return format!("{text}{}0", syntax::DECIMAL),
+            }
+        }
+        TokenType::String(text) => return string(text),
+        TokenType::Bool(b) => return b.to_string(),
+        _ => panic!(),
+    }
+}
+
+// Whether a bracket opens the arguments of a call or `$`, or an array.
+// Other brackets group operations, index or are part of a type.
+fn opens_list(bracket: &Token, before: Option<&Token>) -> bool {
+    let before = match before {
+        Some(Token(token_type, _)) => token_type,
+        None => return false,
+    };
+
+    match bracket.0 {
+        TokenType::Operator(Operator::OpenParen) => {
+            return matches!(before, TokenType::Operator(Operator::Dot | Operator::New))
+        }
+        _ => {
+            return !matches!(
+                before,
+                TokenType::Identity(_)
+                    | TokenType::Operator(Operator::CloseBracket | Operator::CloseParen)
+            )
+        }
+    }
+}
+
+fn pos(loc: &FileLocation) -> Pos {
+    match loc {
+        FileLocation::Loc {
+            start_line,
+            start_col,
+            ..
+        } => return (*start_line, *start_col),
+        _ => panic!(),
+    }
+}
+
+fn end_line(loc: &FileLocation) -> usize {
+    match loc {
+        FileLocation::Loc { end_line, .. } => return *end_line,
+        _ => panic!(),
+    }
+}
+
+// Position of the leftmost token of an expression
+fn expression_pos(expression: &OperandExpression) -> Pos {
+    match &expression.0 {
+        OperandExpressionValue::Binary { left, .. } | OperandExpressionValue::Dot { left, .. } => {
+            return expression_pos(left)
+        }
+        _ => return pos(&expression.1),
+    }
+}
+
+fn term_pos(term: &Term) -> Pos {
+    match term {
+        Term::Print { operand_block, .. } => return expression_pos(operand_block),
+        Term::DeclareVar { vartype, loc, .. } => return pos(vartype.get_location()).min(pos(loc)),
+        Term::Return { value } | Term::Call { value } => return expression_pos(value),
+        Term::UpdateVar { var, .. } => return pos(&var.loc),
+        Term::If { conditional, .. } | Term::Loop { conditional, .. } => {
+            return expression_pos(conditional)
+        }
+        Term::Break(loc) | Term::Continue(loc) => return pos(loc),
+    }
+}
+
+fn function_pos(func: &Function) -> Pos {
+    return pos(func.returntype.get_location()).min(pos(&func.loc));
+}
+
+fn item_pos(item: &Item) -> Pos {
+    match item {
+        Item::Import(import) => return pos(&import.objects[0].1),
+        Item::Struct(astruct) => return pos(&astruct.loc),
+        Item::Function(func) => return function_pos(func),
+    }
+}
+
+fn member_pos(member: &Member) -> Pos {
+    match member {
+        Member::Property(property) => return pos(property.argtype.get_location()),
+        Member::Method(method) => return function_pos(method),
+    }
+}
+
+fn format_source(source: SourceId, style: &Style) -> Result<String, ErrorType> {
+    let text = source_map::source_text(source);
+    let tokens = match lexer::lex(&text, true, &source, "", &[]) {
+        Ok(tokens) => tokens,
+        Err(err) => return Err(ErrorType::Lexer(err)),
+    };
+
+    let mut comments = Vec::new();
+    let mut list_comments = Vec::new();
+    let mut code: Vec<Token> = Vec::new();
+    // Brackets around the current token, with whether each opens a list
+    let mut brackets: Vec<(Pos, bool)> = Vec::new();
+    for token in tokens {
+        match &token.0 {
+            TokenType::Comment(text) => {
+                let comment = Comment {
+                    text: text.clone(),
+                    pos: pos(&token.1),
+                };
+                match brackets.iter().rev().find(|(_, list)| *list) {
+                    Some((open, _)) => list_comments.push((*open, comment)),
+                    None => comments.push(comment),
+                }
+                continue;
+            }
+            TokenType::Operator(Operator::OpenParen | Operator::OpenBracket) => {
+                brackets.push((pos(&token.1), opens_list(&token, code.last())));
+            }
+            TokenType::Operator(Operator::CloseParen | Operator::CloseBracket) => {
+                brackets.pop();
+            }
+            _ => {}
+        }
+
+        code.push(token);
+    }
+
+    let program = parser::parse(code.clone(), &source)?;
+
+    let mut formatter = Formatter {
+        style,
+        syntax_map: get_syntax_map(),
+        precedence: get_precedent_map(),
+        tokens: code,
+        comments,
+        list_comments,
+        next_comment: 0,
+        lines: Vec::new(),
+        indent: 0,
+        last_line: 0,
+        opened: false,
+        blank: false,
+    };
+    formatter.program(&program, source);
+
+    // Files end with a single blank line
+    let mut text = formatter.lines.join("\n");
+    text.push_str("\n\n");
+    return Ok(text);
+}
+
+// Format a program from the source map. Programs that do not lex or parse
+// are left alone and their error is returned.
+pub fn format(source: SourceId, style: &Style) -> Result<String, ErrorType> {
+    let text = format_source(source, style)?;
+
+    // Formatting only moves whitespace and comments, so formatted code must
+    // format to itself
+    let formatted = source_map::add_source(source_map::source_path(source), text.clone());
+    match format_source(formatted, style) {
+        Ok(again) if again == text => return Ok(text),
+        _ => panic!("Formatting changed the program"),
+    }
+}
@@ -1,11 +1,11 @@
 pub mod syntax;
 pub mod tokens;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
 use crate::{
     active_parser::names::PREFIX_PROTECTED_NAMES,
-    errors::{FileLocation, LexerError},
+    errors::{codes, FileLocation, LexerError, SourceId},
 };
 
 use self::{
@@ -47,7 +47,7 @@ impl Section {
 }
 
 struct FileLocationModel {
-    file: PathBuf,
+    file: SourceId,
     start_line: usize,
     end_line: usize,
     start_col: usize,
@@ -56,7 +56,7 @@ struct FileLocationModel {
 impl FileLocationModel {
     fn build(&self) -> FileLocation {
         FileLocation::Loc {
-            file: self.file.clone(),
+            file: self.file,
             start_line: self.start_line,
             end_line: self.end_line,
             start_col: self.start_col,
@@ -236,7 +236,13 @@ fn handle_char(
             }
 
             if VARIABLE_ALLOWED_EXTRA_CHARS_START.contains(c) {
-                return Err(LexerError(format!("The character '{c}' is permitted at the start of an identity token but not within an identity token."), positioning.build()));
+                return Err(LexerError::new(
+                    format!(
+                        "The character '{c}' is permitted at the start of an identity token but not within an identity token."
+                    ),
+                    positioning.build(),
+                    codes::L0002,
+                ));
             }
 
             // Take ownership of word
@@ -250,6 +256,12 @@ fn handle_char(
                     TokenType::KeyWord(syntax_map.keywords[content].clone()),
                     positioning.build(),
                 ))
+            } else if syntax_map.operators.contains_key(content) {
+                // Complete word operator token: xor
+                result.push(Token(
+                    TokenType::Operator(syntax_map.operators[content].clone()),
+                    positioning.build(),
+                ))
             } else if syntax_map.bools.contains_key(content) {
                 // Complete bool token
                 result.push(Token(
@@ -317,9 +329,10 @@ fn handle_char(
                     );
                 } else {
                     // Mark invalid operator
-                    return Err(LexerError(
+                    return Err(LexerError::new(
                         format!("Error invalid operator: {}", section.content),
                         positioning.build(),
+                        codes::L0001,
                     ));
                 }
             }
@@ -371,7 +384,7 @@ fn handle_char(
 pub fn lex(
     input: &String,
     lex_comments: bool,
-    file: &PathBuf,
+    file: &SourceId,
     id_prefix: &str,
     prefix_exclude: &[String],
 ) -> Result<Vec<Token>, LexerError> {
@@ -386,7 +399,7 @@ pub fn lex(
 
     // Create token position tracker
     let mut positioning = FileLocationModel {
-        file: file.clone(),
+        file: *file,
         start_line: 0,
         end_line: 0,
         start_col: 0,
@@ -418,5 +431,14 @@ pub fn lex(
         }
     }
 
+    // A string still open at the end of the input would otherwise be lost
+    if let SectionState::String(quote) = section.state {
+        return Err(LexerError::new(
+            format!("String is missing its closing {quote}."),
+            positioning.build(),
+            codes::L0003,
+        ));
+    }
+
     return Ok(result);
 }
@@ -11,6 +11,27 @@ pub const LINE_TERMINATOR: char = '~';
 pub const STRING_QUOTES: &str = "\"'`";
 pub const IGNORED_IN_NUMBERS: &str = "_";
 
+// Keywords that can start a term inside a block
+pub const TERM_KEYWORDS: [&str; 10] = [
+    "let", "updt", "cll", "print", "println", "if", "loop", "break", "continue", "return",
+];
+
+// Keywords that can start an item at the top level of a file
+pub const TOP_LEVEL_KEYWORDS: [&str; 3] = ["struct", "func", "import"];
+
+// Keywords from other languages and what they are written as here
+pub const KEYWORD_ALIASES: [(&str, &str); 9] = [
+    ("var", "let"),
+    ("const", "let"),
+    ("update", "updt"),
+    ("call", "cll"),
+    ("while", "loop"),
+    ("for", "loop"),
+    ("class", "struct"),
+    ("fn", "func"),
+    ("function", "func"),
+];
+
 pub struct SyntaxMap<'a> {
     pub operators: HashMap<&'a str, Operator>,
     pub keywords: HashMap<&'a str, KeyWord>,
@@ -48,6 +69,11 @@ pub fn get_syntax_map() -> SyntaxMap<'static> {
             ("!", Operator::Not),
             ("&&", Operator::And),
             ("||", Operator::Or),
+            ("&", Operator::BitAnd),
+            ("|", Operator::BitOr),
+            ("xor", Operator::BitXor),
+            ("<<", Operator::ShiftLeft),
+            (">>", Operator::ShiftRight),
             (".", Operator::Dot),
             (":", Operator::Colon),
             (",", Operator::Comma),
@@ -74,6 +74,12 @@ pub enum Operator {
     And,
     Or,
 
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+
     Dot,
     Colon,
     Comma,
@@ -0,0 +1,437 @@
+pub mod rules;
+
+use std::{cell::RefCell, collections::HashSet, path::Path, rc::Rc};
+
+use crate::{
+    active_parser::{
+        names as nm, AFunc, AFuncBlock, ALiteral, AObject, AObjectType, AOperandExpression,
+        AOperandExpressionValue, AProgram, ATerm, ATermBlock, AType,
+    },
+    errors::{FileLocation, LintWarning, ManagerError, Note, SourceId},
+};
+
+use self::rules::{Rule, Rules};
+
+struct Var {
+    name: String,
+    // Only variables declared with let are reported when unused
+    loc: Option<FileLocation>,
+    used: bool,
+}
+
+struct Linter<'a> {
+    source: SourceId,
+    rules: &'a Rules,
+    scopes: Vec<Vec<Var>>,
+    // Global objects referred to from the linted file
+    used: HashSet<String>,
+    warnings: Vec<LintWarning>,
+}
+impl<'a> Linter<'a> {
+    fn in_source(&self, loc: &FileLocation) -> bool {
+        match loc {
+            FileLocation::Loc { file, .. } | FileLocation::End { file } => {
+                return *file == self.source
+            }
+            FileLocation::None => return false,
+        }
+    }
+
+    fn warn(&mut self, rule: Rule, msg: String, loc: &FileLocation, notes: Vec<Note>) {
+        if self.rules.enabled(rule) && self.in_source(loc) {
+            self.warnings
+                .push(LintWarning(msg, loc.clone(), rule.code(), notes));
+        }
+    }
+
+    fn find_var(&mut self, name: &str) -> Option<&mut Var> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(var) = scope.iter_mut().rev().find(|var| var.name == name) {
+                return Some(var);
+            }
+        }
+
+        return None;
+    }
+
+    fn push_scope(&mut self, names: Vec<String>) {
+        let vars = names
+            .into_iter()
+            .map(|name| Var {
+                name,
+                loc: None,
+                used: true,
+            })
+            .collect();
+
+        self.scopes.push(vars);
+    }
+
+    fn pop_scope(&mut self) {
+        for var in self.scopes.pop().unwrap() {
+            if var.used || var.name.starts_with('_') {
+                continue;
+            }
+
+            if let Some(loc) = var.loc {
+                let msg = format!("Variable {} is never used.", var.name);
+                self.warn(Rule::UnusedVariable, msg, &loc, Vec::new());
+            }
+        }
+    }
+
+    fn use_name(&mut self, name: &String) {
+        match self.find_var(name) {
+            Some(var) => var.used = true,
+            None => {
+                self.used.insert(name.clone());
+            }
+        }
+    }
+
+    fn use_type(&mut self, _type: &Rc<RefCell<AType>>) {
+        match &*_type.borrow() {
+            AType::ArrayObject(inner) => self.use_type(inner),
+            AType::StructObject(astruct) | AType::StructDefRef(astruct) => {
+                self.used.insert(astruct.name.clone());
+            }
+            AType::FuncDefRef(_) | AType::NotYetDefined(..) => {}
+        }
+    }
+
+    fn func(&mut self, func: &AFunc, this: bool) {
+        self.use_type(&func.returntype);
+        for arg in &func.args {
+            self.use_type(&arg._type);
+        }
+
+        let mut names: Vec<String> = func.args.iter().map(|arg| arg.name.clone()).collect();
+        if this {
+            names.push(nm::THIS.to_string());
+        }
+
+        if let AFuncBlock::TermsLang(block) = &func.block {
+            self.push_scope(names);
+            self.block(&block.borrow());
+            self.pop_scope();
+        }
+    }
+
+    fn block(&mut self, block: &ATermBlock) {
+        let terms = match block {
+            ATermBlock::A { terms } => terms,
+            ATermBlock::NotYetEvaluated(_) => panic!(),
+        };
+
+        self.push_scope(Vec::new());
+
+        let mut exited = None;
+        for term in terms {
+            // Only the first unreachable term is reported
+            if let Some(exit) = exited.take() {
+                self.warn(
+                    Rule::Unreachable,
+                    format!("Unreachable code after {exit}."),
+                    &term_loc(term),
+                    Vec::new(),
+                );
+            }
+
+            self.term(term);
+
+            exited = match term {
+                ATerm::Break(_) => Some("break"),
+                ATerm::Continue(_) => Some("continue"),
+                ATerm::Return { .. } => Some("return"),
+                _ => None,
+            };
+        }
+
+        self.pop_scope();
+    }
+
+    fn term(&mut self, term: &ATerm) {
+        match term {
+            ATerm::Print { value, .. } | ATerm::Return { value } => self.expression(value),
+            ATerm::DeclareVar {
+                name,
+                _vartype,
+                value,
+                loc,
+            } => {
+                self.expression(value);
+                self.use_type(_vartype);
+
+                let shadowed = self.find_var(name).map(|var| var.loc.clone());
+                if let Some(shadowed) = shadowed {
+                    let notes = match shadowed {
+                        Some(shadowed) => {
+                            vec![Note(format!("{name} first declared here"), shadowed)]
+                        }
+                        None => Vec::new(),
+                    };
+                    let msg = format!("Variable {name} shadows an earlier variable.");
+                    self.warn(Rule::Shadowing, msg, loc, notes);
+                }
+
+                self.scopes.last_mut().unwrap().push(Var {
+                    name: name.clone(),
+                    loc: Some(loc.clone()),
+                    used: false,
+                });
+            }
+            ATerm::UpdateVar { value, var } => {
+                self.expression(value);
+
+                // Setting a variable does not use it, but setting its fields does
+                self.object(var, var.sub.is_some());
+
+                if let AOperandExpressionValue::Object(object) = &value.value {
+                    if same_object(object, var) {
+                        let msg = format!("Variable {} is assigned to itself.", object_name(var));
+                        self.warn(Rule::SelfAssignment, msg, &var.loc, Vec::new());
+                    }
+                }
+            }
+            ATerm::If {
+                conditional,
+                block,
+                else_block,
+            } => {
+                self.expression(conditional);
+                if is_constant(conditional) {
+                    let msg = "Condition is always the same.".to_string();
+                    self.warn(Rule::ConstantCondition, msg, &conditional.loc, Vec::new());
+                }
+
+                self.block(block);
+                self.block(else_block);
+            }
+            ATerm::Loop {
+                counter,
+                conditional,
+                block,
+                ..
+            } => {
+                self.push_scope(vec![counter.clone()]);
+
+                self.expression(conditional);
+                let forever = matches!(
+                    conditional.value,
+                    AOperandExpressionValue::Literal(ALiteral::Bool(true))
+                );
+                if is_constant(conditional) && !forever {
+                    let msg = "Condition is always the same.".to_string();
+                    self.warn(Rule::ConstantCondition, msg, &conditional.loc, Vec::new());
+                }
+
+                self.block(block);
+                self.pop_scope();
+            }
+            ATerm::Call { value } => {
+                self.expression(value);
+
+                let returns_value = match &*value.get_type().borrow() {
+                    AType::StructObject(astruct) => astruct.name != nm::NULL,
+                    _ => true,
+                };
+                if returns_value {
+                    let msg = "Return value of call is never used.".to_string();
+                    self.warn(Rule::UnusedReturn, msg, &value.loc, Vec::new());
+                }
+            }
+            ATerm::Break(_) | ATerm::Continue(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &AOperandExpression) {
+        match &expression.value {
+            AOperandExpressionValue::Dot { left, right } => {
+                self.expression(left);
+                self.sub_object(right);
+            }
+            AOperandExpressionValue::And { left, right }
+            | AOperandExpressionValue::Or { left, right } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            AOperandExpressionValue::Object(object) => self.object(object, true),
+            AOperandExpressionValue::Literal(_) => {}
+            AOperandExpressionValue::Create { _type, args } => {
+                self.use_type(_type);
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+            AOperandExpressionValue::Array(values) => {
+                for value in values {
+                    self.expression(value);
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, object: &AObject, read: bool) {
+        if let AObjectType::Identity(id) = &object.kind {
+            if read {
+                self.use_name(id);
+            }
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub);
+        }
+    }
+
+    // Fields and calls after the first name in an object
+    fn sub_object(&mut self, object: &AObject) {
+        if let AObjectType::Call(call) = &object.kind {
+            for arg in &call.args {
+                self.expression(arg);
+            }
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub);
+        }
+    }
+}
+
+fn term_loc(term: &ATerm) -> FileLocation {
+    match term {
+        ATerm::Print { value, .. } | ATerm::Return { value } | ATerm::Call { value } => {
+            return value.loc.clone()
+        }
+        ATerm::DeclareVar { loc, .. } | ATerm::Break(loc) | ATerm::Continue(loc) => {
+            return loc.clone()
+        }
+        ATerm::UpdateVar { var, .. } => return var.loc.clone(),
+        ATerm::If { conditional, .. } | ATerm::Loop { conditional, .. } => {
+            return conditional.loc.clone()
+        }
+    }
+}
+
+// Name of an object made only of names, such as `point.x`
+fn object_name(object: &AObject) -> String {
+    let name = match &object.kind {
+        AObjectType::Identity(id) => id.clone(),
+        AObjectType::Call(_) => "()".to_string(),
+    };
+
+    match &object.sub {
+        Some(sub) => return format!("{name}.{}", object_name(sub)),
+        None => return name,
+    }
+}
+
+// Whether two objects name the same thing without calling anything
+fn same_object(a: &AObject, b: &AObject) -> bool {
+    let same_kind = match (&a.kind, &b.kind) {
+        (AObjectType::Identity(a), AObjectType::Identity(b)) => a == b,
+        _ => false,
+    };
+
+    match (&a.sub, &b.sub) {
+        (Some(a_sub), Some(b_sub)) => return same_kind && same_object(a_sub, b_sub),
+        (None, None) => return same_kind,
+        _ => return false,
+    }
+}
+
+// Whether an expression is made only of literals and operations on them
+fn is_constant(expression: &AOperandExpression) -> bool {
+    match &expression.value {
+        AOperandExpressionValue::Literal(_) => return true,
+        AOperandExpressionValue::And { left, right }
+        | AOperandExpressionValue::Or { left, right } => {
+            return is_constant(left) && is_constant(right)
+        }
+        AOperandExpressionValue::Dot { left, right } => {
+            return is_constant(left) && constant_sub_object(right)
+        }
+        _ => return false,
+    }
+}
+
+fn constant_sub_object(object: &AObject) -> bool {
+    let constant = match &object.kind {
+        AObjectType::Identity(_) => true,
+        AObjectType::Call(call) => call.args.iter().all(is_constant),
+    };
+
+    match &object.sub {
+        Some(sub) => return constant && constant_sub_object(sub),
+        None => return constant,
+    }
+}
+
+// Lint the parts of a program that come from one source
+pub fn lint(program: &AProgram, source: SourceId, rules: &Rules) -> Vec<LintWarning> {
+    let mut linter = Linter {
+        source,
+        rules,
+        scopes: Vec::new(),
+        used: HashSet::new(),
+        warnings: Vec::new(),
+    };
+
+    for func in &program.functions {
+        if linter.in_source(&func.loc) {
+            linter.func(func, false);
+        }
+    }
+
+    for astruct in &program.structs {
+        if !linter.in_source(&astruct.loc) {
+            continue;
+        }
+
+        for field in astruct.fields.values() {
+            linter.use_type(&field._type);
+        }
+
+        for method in astruct.methods.values() {
+            linter.func(method, true);
+        }
+    }
+
+    for func in &program.functions {
+        if func.name != nm::F_MAIN && !linter.used.contains(&func.name) {
+            let msg = format!("Function {} is never used.", func.name);
+            linter.warn(Rule::UnusedFunction, msg, &func.loc, Vec::new());
+        }
+    }
+
+    for import in &program.imports {
+        for (name, loc) in &import.objects {
+            if !linter.used.contains(name) {
+                let msg = format!("Imported object {name} is never used.");
+                linter.warn(Rule::UnusedImport, msg, loc, Vec::new());
+            }
+        }
+    }
+
+    // Methods are stored by name, so put warnings back in source order
+    let mut warnings = linter.warnings;
+    warnings.sort_by_key(|warning| match warning.1 {
+        FileLocation::Loc {
+            start_line,
+            start_col,
+            ..
+        } => (start_line, start_col),
+        _ => (usize::MAX, 0),
+    });
+
+    return warnings;
+}
+
+// Lint a file using the rules set for it
+pub fn lint_file(
+    program: &AProgram,
+    path: &Path,
+    source: SourceId,
+) -> Result<Vec<LintWarning>, ManagerError> {
+    let rules = rules::file_rules(path, source)?;
+    return Ok(lint(program, source, &rules));
+}
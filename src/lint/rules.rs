@@ -0,0 +1,211 @@
+use std::{fs, path::Path};
+
+use crate::{
+    errors::{codes, source_map, suggest, ErrorCode, FileLocation, ManagerError, SourceId},
+    lexer::{self, tokens::TokenType},
+};
+
+// File in a program's directory, or one above it, that sets the lint rules
+const CONFIG_FILE: &str = ".termslint";
+
+// Start of a comment that sets lint rules for its file
+const COMMENT_PREFIX: &str = "lint:";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    UnusedVariable,
+    UnusedFunction,
+    UnusedImport,
+    Shadowing,
+    Unreachable,
+    SelfAssignment,
+    ConstantCondition,
+    UnusedReturn,
+}
+impl Rule {
+    pub const ALL: [Rule; 8] = [
+        Rule::UnusedVariable,
+        Rule::UnusedFunction,
+        Rule::UnusedImport,
+        Rule::Shadowing,
+        Rule::Unreachable,
+        Rule::SelfAssignment,
+        Rule::ConstantCondition,
+        Rule::UnusedReturn,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::UnusedVariable => return "unused_variable",
+            Rule::UnusedFunction => return "unused_function",
+            Rule::UnusedImport => return "unused_import",
+            Rule::Shadowing => return "shadowing",
+            Rule::Unreachable => return "unreachable",
+            Rule::SelfAssignment => return "self_assignment",
+            Rule::ConstantCondition => return "constant_condition",
+            Rule::UnusedReturn => return "unused_return",
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Rule::UnusedVariable => return codes::W0001,
+            Rule::UnusedFunction => return codes::W0002,
+            Rule::UnusedImport => return codes::W0003,
+            Rule::Shadowing => return codes::W0004,
+            Rule::Unreachable => return codes::W0005,
+            Rule::SelfAssignment => return codes::W0006,
+            Rule::ConstantCondition => return codes::W0007,
+            Rule::UnusedReturn => return codes::W0008,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Rule> {
+        return Rule::ALL.into_iter().find(|rule| rule.name() == name);
+    }
+}
+
+// The rules turned off for a file. Every rule warns unless allowed.
+pub struct Rules {
+    allowed: Vec<Rule>,
+}
+impl Rules {
+    pub fn new() -> Self {
+        Rules {
+            allowed: Vec::new(),
+        }
+    }
+
+    pub fn enabled(&self, rule: Rule) -> bool {
+        return !self.allowed.contains(&rule);
+    }
+
+    // Apply a setting such as `allow shadowing, unused_return`
+    fn apply(&mut self, setting: &str, loc: &FileLocation) -> Result<(), ManagerError> {
+        let setting = setting.trim();
+        let words = setting.replace(',', " ");
+        let mut words = words.split_whitespace();
+
+        let allow = match words.next() {
+            Some("allow") => true,
+            Some("warn") => false,
+            _ => {
+                return Err(ManagerError::new(
+                    format!("Expected allow or warn at the start of lint setting `{setting}`."),
+                    loc.clone(),
+                    codes::M0006,
+                ))
+            }
+        };
+
+        let mut n_rules = 0;
+        for word in words {
+            let rule = match Rule::from_name(word) {
+                Some(rule) => rule,
+                None => {
+                    return Err(ManagerError::new(
+                        format!(
+                            "Unknown lint rule {word}.{}",
+                            suggest::did_you_mean(word, Rule::ALL.map(|rule| rule.name()))
+                        ),
+                        loc.clone(),
+                        codes::M0006,
+                    ))
+                }
+            };
+
+            self.allowed.retain(|allowed| *allowed != rule);
+            if allow {
+                self.allowed.push(rule);
+            }
+
+            n_rules += 1;
+        }
+
+        if n_rules == 0 {
+            return Err(ManagerError::new(
+                format!("Expected rule names in lint setting `{setting}`."),
+                loc.clone(),
+                codes::M0006,
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// Rules for a program file, from the nearest config file and then the
+// file's own lint comments
+pub fn file_rules(path: &Path, source: SourceId) -> Result<Rules, ManagerError> {
+    let mut rules = Rules::new();
+
+    if let Some(config) = find_config(path) {
+        apply_config(&mut rules, &config)?;
+    }
+
+    let text = source_map::source_text(source);
+    let tokens = match lexer::lex(&text, true, &source, "", &[]) {
+        Ok(tokens) => tokens,
+        // Lexer errors are reported when the program is checked
+        Err(_) => return Ok(rules),
+    };
+
+    for token in tokens {
+        if let TokenType::Comment(comment) = &token.0 {
+            if let Some(setting) = comment.trim().strip_prefix(COMMENT_PREFIX) {
+                rules.apply(setting, &token.1)?;
+            }
+        }
+    }
+
+    return Ok(rules);
+}
+
+fn find_config(path: &Path) -> Option<std::path::PathBuf> {
+    let path = match fs::canonicalize(path) {
+        Ok(path) => path,
+        Err(_) => path.to_path_buf(),
+    };
+
+    for dir in path.ancestors().skip(1) {
+        let config = dir.join(CONFIG_FILE);
+        if config.is_file() {
+            return Some(config);
+        }
+    }
+
+    return None;
+}
+
+fn apply_config(rules: &mut Rules, config: &Path) -> Result<(), ManagerError> {
+    let text = match fs::read_to_string(config) {
+        Ok(text) => text,
+        Err(err) => {
+            return Err(ManagerError::new(
+                format!("Could not open lint config {} | {err}", config.display()),
+                FileLocation::None,
+                codes::M0001,
+            ))
+        }
+    };
+
+    // Keep the config in the source map so errors can point into it
+    let source = source_map::add_source(config.to_path_buf(), text.clone());
+
+    for (line_idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.trim().starts_with('#') {
+            continue;
+        }
+
+        let loc = FileLocation::Loc {
+            file: source,
+            start_line: line_idx,
+            end_line: line_idx,
+            start_col: 1,
+            end_col: line.chars().count(),
+        };
+        rules.apply(line, &loc)?;
+    }
+
+    return Ok(());
+}
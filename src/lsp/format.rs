@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use crate::{
+    errors::{source_map, ErrorType},
+    formmatter::{
+        self, config,
+        diff::{self, Edit},
+    },
+    lsp::{Position, Range, TextEdit},
+};
+
+// Edits for `textDocument/formatting`, or `textDocument/rangeFormatting`
+// when a range is given. Documents that do not lex or parse are left alone.
+pub fn formatting(file: &Path, text: String, range: Option<Range>) -> String {
+    let style = match config::file_style(file) {
+        Ok(style) => style,
+        Err(err) => return super::response(None, &[ErrorType::Manager(err)]),
+    };
+
+    let source = source_map::add_source(file.to_path_buf(), text.clone());
+    let formatted = match formmatter::format(source, &style) {
+        Ok(formatted) => formatted,
+        Err(err) => return super::response(None, &[err]),
+    };
+
+    let edits: Vec<String> = text_edits(&text, &formatted)
+        .iter()
+        .filter(|edit| match range {
+            Some(range) => overlaps(&edit.range, &range),
+            None => true,
+        })
+        .map(|edit| edit.json())
+        .collect();
+
+    return super::response(Some(format!("[{}]", edits.join(","))), &[]);
+}
+
+// Edits turning a document into its formatted text that replace only the
+// lines that differ
+pub fn text_edits(old: &str, new: &str) -> Vec<TextEdit> {
+    // Lines keep their ends so a change to the end of the file is kept
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let mut text_edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut change: Option<(usize, usize)> = None;
+
+    for edit in diff::edits(&old_lines, &new_lines) {
+        if edit == Edit::Keep {
+            if let Some((old_start, new_start)) = change.take() {
+                push_run(
+                    &mut text_edits,
+                    &old_lines,
+                    &new_lines,
+                    (old_start, i),
+                    (new_start, j),
+                );
+            }
+        } else if change.is_none() {
+            change = Some((i, j));
+        }
+
+        match edit {
+            Edit::Keep => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Remove => i += 1,
+            Edit::Add => j += 1,
+        }
+    }
+
+    if let Some((old_start, new_start)) = change {
+        push_run(
+            &mut text_edits,
+            &old_lines,
+            &new_lines,
+            (old_start, i),
+            (new_start, j),
+        );
+    }
+
+    return text_edits;
+}
+
+// A run that changes each line in place is split into an edit per line, so
+// a range only takes the lines inside it. Runs that add or remove lines are
+// replaced whole.
+fn push_run(
+    text_edits: &mut Vec<TextEdit>,
+    old_lines: &[&str],
+    new_lines: &[&str],
+    (old_start, old_end): (usize, usize),
+    (new_start, new_end): (usize, usize),
+) {
+    if old_end - old_start == new_end - new_start {
+        for (i, j) in (old_start..old_end).zip(new_start..new_end) {
+            text_edits.push(replace(old_lines, i..i + 1, new_lines[j].to_string()));
+        }
+    } else {
+        text_edits.push(replace(
+            old_lines,
+            old_start..old_end,
+            new_lines[new_start..new_end].concat(),
+        ));
+    }
+}
+
+fn replace(old_lines: &[&str], lines: std::ops::Range<usize>, new_text: String) -> TextEdit {
+    let start = Position {
+        line: lines.start,
+        character: 0,
+    };
+
+    // Only the last line of a document can be missing its line end
+    let end = match old_lines.get(lines.end) {
+        Some(_) => Position {
+            line: lines.end,
+            character: 0,
+        },
+        None => Position::end_of(&old_lines.concat()),
+    };
+
+    return TextEdit {
+        range: Range { start, end },
+        new_text,
+    };
+}
+
+// Whether an edit touches the lines of a range. A range ending at the start
+// of a line does not include that line.
+fn overlaps(edit: &Range, range: &Range) -> bool {
+    let last_line = match range.end.character == 0 && range.end.line > range.start.line {
+        true => range.end.line - 1,
+        false => range.end.line,
+    };
+
+    // An edit that only inserts lines touches the line it inserts before
+    let edit_last = match edit.end.character == 0 && edit.end.line > edit.start.line {
+        true => edit.end.line - 1,
+        false => edit.end.line,
+    };
+
+    return edit.start.line <= last_line && edit_last >= range.start.line;
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use crate::{
+    active_parser::{names as nm, AFunc, AFuncBlock, AProgram, ATerm, ATermBlock},
+    errors::{json_string, FileLocation, SourceId},
+    lexer::tokens::{Token, TokenType},
+    lsp::{signature, Document, Position, Range},
+};
+
+// Kinds of inlay hint from the LSP specification
+const TYPE: usize = 1;
+const PARAMETER: usize = 2;
+
+struct Hint {
+    position: Position,
+    label: String,
+    kind: usize,
+}
+impl Hint {
+    fn json(&self) -> String {
+        return format!(
+            "{{\"position\":{},\"label\":{},\"kind\":{},\"paddingRight\":true}}",
+            self.position.json(),
+            json_string(&self.label),
+            self.kind
+        );
+    }
+}
+
+// Locations of the loop counters in a function, which are always ints
+fn counters(block: &ATermBlock, locs: &mut Vec<FileLocation>) {
+    let terms = match block {
+        ATermBlock::A { terms } => terms,
+        ATermBlock::NotYetEvaluated(_) => panic!(),
+    };
+
+    for term in terms {
+        match term {
+            ATerm::Loop { block, loc, .. } => {
+                locs.push(loc.clone());
+                counters(block, locs);
+            }
+            ATerm::If {
+                block, else_block, ..
+            } => {
+                counters(block, locs);
+                counters(else_block, locs);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn funcs(program: &AProgram) -> Vec<&AFunc> {
+    let mut funcs: Vec<&AFunc> = program.functions.iter().map(|func| &**func).collect();
+    for astruct in &program.structs {
+        funcs.extend(astruct.methods.values().map(|method| &**method));
+    }
+
+    return funcs;
+}
+
+// Parameter names before the arguments of calls, and the type of loop
+// counters
+fn hints(program: &AProgram, source: SourceId, tokens: &[Token], doc: &Document) -> Vec<Hint> {
+    let mut hints = Vec::new();
+
+    for call in signature::calls(program, source, tokens) {
+        for (arg, param) in call.args.iter().zip(&call.func.args) {
+            // An argument that is only the parameter's name needs no hint
+            let alone = arg + 1 == call.close || call.commas.contains(&(arg + 1));
+            let param_name = signature::unprefixed(&param.name);
+            let same_name = match &tokens[*arg].0 {
+                TokenType::Identity(name) => alone && name == param_name,
+                _ => false,
+            };
+            if param_name.is_empty() || same_name {
+                continue;
+            }
+
+            if let Some(range) = doc.name_range(&tokens[*arg].1, "") {
+                hints.push(Hint {
+                    position: range.start,
+                    label: format!("{param_name}:"),
+                    kind: PARAMETER,
+                });
+            }
+        }
+    }
+
+    let mut locs = Vec::new();
+    for func in funcs(program) {
+        if let AFuncBlock::TermsLang(block) = &func.block {
+            counters(&block.borrow(), &mut locs);
+        }
+    }
+    for loc in locs {
+        match &loc {
+            FileLocation::Loc { file, .. } if *file == source => {}
+            _ => continue,
+        }
+
+        if let Some(range) = doc.name_range(&loc, "") {
+            hints.push(Hint {
+                position: range.start,
+                label: String::from(nm::INT),
+                kind: TYPE,
+            });
+        }
+    }
+
+    hints.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+    return hints;
+}
+
+// `textDocument/inlayHint` for the hints inside a range
+pub fn inlay_hints(file: &Path, text: &str, range: Range) -> String {
+    let analysis = super::analyse(file, text);
+
+    let result = match &analysis.aprogram {
+        Some(aprogram) => {
+            let doc = Document::new(text);
+            let hints: Vec<String> = hints(aprogram, analysis.source, &analysis.tokens, &doc)
+                .iter()
+                .filter(|hint| range.start <= hint.position && hint.position <= range.end)
+                .map(|hint| hint.json())
+                .collect();
+            Some(format!("[{}]", hints.join(",")))
+        }
+        None => None,
+    };
+
+    return super::response(result, &analysis.errors);
+}
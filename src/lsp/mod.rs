@@ -0,0 +1,557 @@
+pub mod format;
+pub mod inlay;
+pub mod rename;
+pub mod semantic;
+pub mod signature;
+pub mod symbols;
+
+use crate::{
+    active_parser::{aparse, names, AProgram},
+    cli::DocumentArgs,
+    errors::{
+        codes, json_string, source_map, ErrorType, FileLocation, LintWarning, LspError,
+        ManagerError, SourceId,
+    },
+    lexer::{
+        self,
+        tokens::{KeyWord, Operator, Token, TokenType},
+    },
+    lint,
+    parser::{parse, Program},
+};
+use std::{collections::HashMap, fs, io::Read, path::Path};
+
+// Position in a document as LSP gives it: lines from zero and characters
+// counted in UTF-16 code units
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+impl Position {
+    // Position just past the end of some text
+    pub fn end_of(text: &str) -> Position {
+        let line = text.matches('\n').count();
+        let last = match text.rfind('\n') {
+            Some(idx) => &text[idx + 1..],
+            None => text,
+        };
+
+        return Position {
+            line,
+            character: last.encode_utf16().count(),
+        };
+    }
+
+    // Read a position written as `line:character`
+    pub fn parse(text: &str) -> Option<Position> {
+        let (line, character) = text.split_once(':')?;
+        return Some(Position {
+            line: line.trim().parse().ok()?,
+            character: character.trim().parse().ok()?,
+        });
+    }
+
+    pub fn json(&self) -> String {
+        return format!(
+            "{{\"line\":{},\"character\":{}}}",
+            self.line, self.character
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+impl Range {
+    // Read a range written as `line:character-line:character`
+    pub fn parse(text: &str) -> Option<Range> {
+        let (start, end) = text.split_once('-')?;
+        return Some(Range {
+            start: Position::parse(start)?,
+            end: Position::parse(end)?,
+        });
+    }
+
+    pub fn json(&self) -> String {
+        return format!(
+            "{{\"start\":{},\"end\":{}}}",
+            self.start.json(),
+            self.end.json()
+        );
+    }
+}
+
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+impl TextEdit {
+    pub fn json(&self) -> String {
+        return format!(
+            "{{\"range\":{},\"newText\":{}}}",
+            self.range.json(),
+            json_string(&self.new_text)
+        );
+    }
+}
+
+// Lines of a document, used to turn the lexer's locations into positions
+pub struct Document<'a> {
+    lines: Vec<&'a str>,
+}
+impl<'a> Document<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Document {
+            lines: text.split('\n').collect(),
+        }
+    }
+
+    // Position of a line and column from the lexer, which counts columns
+    // in characters from one
+    pub fn position(&self, line: usize, col: usize) -> Position {
+        let character = match self.lines.get(line) {
+            Some(text) => text.chars().take(col - 1).map(char::len_utf16).sum(),
+            None => col - 1,
+        };
+
+        return Position { line, character };
+    }
+
+    // Column the lexer gives the character at a position
+    pub fn lexer_col(&self, pos: Position) -> usize {
+        let mut units = 0;
+        let mut col = 1;
+        if let Some(text) = self.lines.get(pos.line) {
+            for c in text.chars() {
+                if units >= pos.character {
+                    break;
+                }
+                units += c.len_utf16();
+                col += 1;
+            }
+        }
+
+        return col;
+    }
+
+    // Range of a name starting at a location, if the location is in the
+    // document
+    pub fn name_range(&self, loc: &FileLocation, name: &str) -> Option<Range> {
+        if let FileLocation::Loc {
+            start_line,
+            start_col,
+            ..
+        } = loc
+        {
+            return Some(self.range(*start_line, *start_col, name));
+        }
+
+        return None;
+    }
+
+    // Range of a name starting at a line and column from the lexer
+    pub fn range(&self, line: usize, col: usize, name: &str) -> Range {
+        let start = self.position(line, col);
+        let end = Position {
+            line: start.line,
+            character: start.character + name.encode_utf16().count(),
+        };
+
+        return Range { start, end };
+    }
+}
+
+// `file` URI of a path, with the characters URIs reserve escaped
+pub fn uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri += &format!("%{byte:02X}"),
+        }
+    }
+
+    return uri;
+}
+
+// What the stages of the compiler make of a document. Later stages are left
+// out once one fails, and its error is kept.
+pub struct Analysis {
+    pub source: SourceId,
+    // Tokens without comments
+    pub tokens: Vec<Token>,
+    pub program: Option<Program>,
+    pub aprogram: Option<AProgram>,
+    pub errors: Vec<ErrorType>,
+}
+
+pub fn analyse(file: &Path, text: &str) -> Analysis {
+    let source = source_map::add_source(file.to_path_buf(), text.to_string());
+    let mut analysis = Analysis {
+        source,
+        tokens: Vec::new(),
+        program: None,
+        aprogram: None,
+        errors: Vec::new(),
+    };
+
+    match lexer::lex(&text.to_string(), false, &source, "", &[]) {
+        Ok(tokens) => analysis.tokens = tokens,
+        Err(err) => {
+            analysis.errors.push(ErrorType::Lexer(err));
+            return analysis;
+        }
+    }
+
+    match parse(analysis.tokens.clone(), &source) {
+        Ok(program) => analysis.program = Some(program),
+        Err(err) => {
+            analysis.errors.push(err);
+            return analysis;
+        }
+    }
+
+    match aparse(analysis.program.as_ref().unwrap()) {
+        Ok(aprogram) => analysis.aprogram = Some(aprogram),
+        Err(err) => analysis.errors.push(ErrorType::AParser(err)),
+    }
+
+    return analysis;
+}
+
+// Text of the document a request is about, which is the editor's unsaved
+// buffer when it is sent on standard input
+pub fn read_document(doc: &DocumentArgs) -> Result<String, ManagerError> {
+    if doc.stdin {
+        let mut text = String::new();
+        return match std::io::stdin().read_to_string(&mut text) {
+            Ok(_) => Ok(text),
+            Err(err) => Err(ManagerError::new(
+                format!("Could not read standard input | {err}"),
+                FileLocation::None,
+                codes::M0001,
+            )),
+        };
+    }
+
+    return match fs::read_to_string(&doc.file) {
+        Ok(text) => Ok(text),
+        Err(err) => Err(ManagerError::new(
+            format!("Could not open program file | {err}"),
+            FileLocation::None,
+            codes::M0001,
+        )),
+    };
+}
+
+// Reply to a request: the LSP result, or null when the request could not be
+// answered, with the errors that stopped it
+pub fn response(result: Option<String>, errors: &[ErrorType]) -> String {
+    let result = match result {
+        Some(result) => result,
+        None => String::from("null"),
+    };
+    let errors: Vec<String> = errors.iter().map(|err| err.json()).collect();
+
+    return format!("{{\"result\":{result},\"errors\":[{}]}}", errors.join(","));
+}
+
+struct TS(Vec<Token>, usize);
+impl TS {
+    fn new(tokens: Vec<Token>) -> TS {
+        TS(tokens, 0)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let t = self.0.get(self.1);
+        self.1 += 1;
+        return t;
+    }
+
+    fn back(&mut self) {
+        self.1 -= 1;
+    }
+}
+
+pub struct Lsp {
+    pub vars: Vec<HashMap<String, ((usize, usize), String)>>,
+    pub errors: Vec<ErrorType>,
+    pub warnings: Vec<LintWarning>,
+    pub structs: HashMap<String, (usize, usize)>,
+    pub functions: HashMap<String, ((usize, usize), String)>,
+}
+
+impl Lsp {
+    fn insert(
+        &mut self,
+        k: String,
+        v: ((usize, usize), String),
+        loc: &FileLocation,
+    ) -> Result<(), LspError> {
+        match self.vars.last_mut() {
+            Some(some) => some,
+            None => {
+                return Err(LspError::new(
+                    format!("Found variable outside of scope."),
+                    loc.clone(),
+                    codes::S0001,
+                ))
+            }
+        }
+        .insert(k, v);
+        return Ok(());
+    }
+
+    pub fn json(self) -> String {
+        let mut string = String::from("{\"variables\":{");
+
+        for scope in self.vars {
+            for var in scope {
+                string += &format!("{}:{{", json_string(&var.0));
+                string += &format!("\"line\":{},", var.1 .0 .0);
+                string += &format!("\"col\":{},", var.1 .0 .1);
+                string += &format!("\"type\":{}}},", json_string(&var.1 .1));
+            }
+        }
+
+        string = match string.strip_suffix(',') {
+            Some(s) => s.to_string(),
+            None => string,
+        };
+
+        string += "},\"errors\":[";
+
+        for error in self.errors {
+            string += &error.json();
+            string += ",";
+        }
+
+        string = match string.strip_suffix(',') {
+            Some(s) => s.to_string(),
+            None => string,
+        };
+
+        string += "],\"warnings\":[";
+
+        for warning in self.warnings {
+            string += &warning.json();
+            string += ",";
+        }
+
+        string = match string.strip_suffix(',') {
+            Some(s) => s.to_string(),
+            None => string,
+        };
+
+        string += "],\"functions\":{";
+
+        for func in self.functions {
+            string += &format!("{}:{{", json_string(&func.0));
+            string += &format!("\"line\":{},", func.1 .0 .0);
+            string += &format!("\"col\":{},", func.1 .0 .1);
+            string += &format!("\"type\":{}}},", json_string(&func.1 .1));
+        }
+
+        string = match string.strip_suffix(',') {
+            Some(s) => s.to_string(),
+            None => string,
+        };
+
+        string += "},\"structs\":{";
+
+        for st in self.structs {
+            string += &format!("{}:{{", json_string(&st.0));
+            string += &format!("\"line\":{},", st.1 .0);
+            string += &format!("\"col\":{}}},", st.1 .1);
+        }
+
+        string = match string.strip_suffix(',') {
+            Some(s) => s.to_string(),
+            None => string,
+        };
+
+        string += "}}";
+
+        return string;
+    }
+}
+
+fn get_type(ts: &mut TS) -> Option<String> {
+    let mut list = 0;
+    if let Some(Token(TokenType::Identity(arg_type), _)) = ts.next() {
+        let var_type = arg_type.clone();
+        while let Some(Token(TokenType::Operator(Operator::OpenBracket), _)) = ts.next() {
+            if let Some(Token(TokenType::Operator(Operator::CloseBracket), _)) = ts.next() {
+                list += 1;
+                continue;
+            }
+
+            return None;
+        }
+
+        ts.back();
+        return format!("{}{}", var_type, String::from("[]").repeat(list)).into();
+    }
+
+    return None;
+}
+
+fn get_args(ts: &mut TS, lsp: &mut Lsp) -> Result<(), LspError> {
+    if let Some(arg_type) = get_type(ts) {
+        let arg_type = arg_type.clone();
+        if let Some(Token(TokenType::Identity(id), loc)) = ts.next() {
+            lsp.insert(id.clone(), (loc.start(), arg_type), loc)?;
+
+            if let Some(Token(TokenType::Operator(Operator::Comma), _)) = ts.next() {
+                get_args(ts, lsp)?;
+            } else {
+                ts.back();
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+fn get_vars(ts: &mut TS, line: usize, col: usize) -> Result<Lsp, LspError> {
+    let mut lsp = Lsp {
+        vars: Vec::new(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        structs: HashMap::new(),
+        functions: HashMap::new(),
+    };
+
+    let mut in_struct: Option<String> = None;
+    let mut update_vars = true;
+    let mut final_vars = Vec::new();
+
+    while let Some(t) = ts.next() {
+        let (token, loc) = (&t.0, &t.1.start());
+
+        if update_vars {
+            if loc.0 > line {
+                update_vars = false;
+                final_vars = lsp.vars.clone();
+            } else if loc.0 == line {
+                if loc.1 > col {
+                    update_vars = false;
+                    final_vars = lsp.vars.clone();
+                }
+            }
+        }
+
+        if let TokenType::KeyWord(KeyWord::Var) = token {
+            if let Some(var_type) = get_type(ts) {
+                let var_type = var_type.clone();
+
+                if let Some(Token(TokenType::Identity(id), loc)) = ts.next() {
+                    lsp.insert(id.clone(), (loc.start(), var_type), loc)?;
+                }
+            }
+        } else if let TokenType::KeyWord(KeyWord::Func) = token {
+            lsp.vars.push(HashMap::new());
+
+            if let Some(return_type) = get_type(ts) {
+                if let Some(Token(TokenType::Identity(name), loc)) = ts.next() {
+                    match in_struct {
+                        Some(ref struct_type) => {
+                            lsp.insert(
+                                names::THIS.to_string(),
+                                (loc.start(), struct_type.clone()),
+                                loc,
+                            )?;
+                        }
+                        None => {
+                            lsp.functions
+                                .insert(name.clone(), (loc.start(), return_type));
+                        }
+                    }
+
+                    if let Some(Token(TokenType::Operator(Operator::Colon), _)) = ts.next() {
+                        get_args(ts, &mut lsp)?;
+                    }
+                }
+            }
+        } else if let TokenType::Operator(Operator::CloseBlock) = token {
+            lsp.vars.pop();
+            if lsp.vars.len() == 0 {
+                in_struct = None;
+            }
+        } else if let TokenType::KeyWord(KeyWord::If) = token {
+            lsp.vars.push(HashMap::new());
+
+            if let Some(Token(TokenType::KeyWord(KeyWord::Else), _)) = ts.next() {
+            } else {
+                ts.back();
+            }
+        } else if let TokenType::KeyWord(KeyWord::Else) = token {
+            lsp.vars.push(HashMap::new());
+        } else if let TokenType::KeyWord(KeyWord::Loop) = token {
+            lsp.vars.push(HashMap::new());
+            if let Some(Token(TokenType::Identity(id), loc)) = ts.next() {
+                lsp.insert(id.clone(), (loc.start(), names::INT.to_string()), loc)?;
+            }
+        } else if let TokenType::KeyWord(KeyWord::Struct) = token {
+            if let Some(Token(TokenType::Identity(id), loc)) = ts.next() {
+                lsp.vars.push(HashMap::new());
+                in_struct = Some(id.clone());
+                lsp.structs.insert(id.clone(), loc.start());
+            }
+        }
+    }
+
+    lsp.vars = final_vars;
+    return Ok(lsp);
+}
+
+pub fn lsp(prog: Vec<Token>, file: &SourceId, line: usize, col: usize, run_parse: bool) -> Lsp {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    if run_parse {
+        match parse(prog.clone(), file) {
+            Ok(program) => match aparse(&program) {
+                Ok(program) => {
+                    let path = source_map::source_path(*file);
+                    match lint::lint_file(&program, &path, *file) {
+                        Ok(ok) => warnings = ok,
+                        Err(err) => errors.push(ErrorType::Manager(err)),
+                    }
+                }
+                Err(err) => errors.push(ErrorType::AParser(err)),
+            },
+            Err(err) => match err {
+                ErrorType::Parser(parser_error) => errors.push(ErrorType::Parser(parser_error)),
+                ErrorType::Lexer(lexer_error) => errors.push(ErrorType::Lexer(lexer_error)),
+                _ => panic!(),
+            },
+        }
+    }
+
+    let mut ts = TS::new(prog);
+    let lsp = match get_vars(&mut ts, line, col) {
+        Ok(mut ok) => {
+            ok.errors.append(&mut errors);
+            ok.warnings = warnings;
+            ok
+        }
+        Err(err) => {
+            errors.push(ErrorType::Lsp(err));
+            Lsp {
+                vars: Vec::new(),
+                errors,
+                warnings,
+                structs: HashMap::new(),
+                functions: HashMap::new(),
+            }
+        }
+    };
+
+    return lsp;
+}
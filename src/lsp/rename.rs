@@ -0,0 +1,772 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    active_parser::{
+        names::{self as nm, PREFIX_PROTECTED_NAMES},
+        AFunc, AFuncBlock, AObject, AObjectType, AOperandExpression, AOperandExpressionValue,
+        AProgram, AStruct, ATerm, ATermBlock, AType,
+    },
+    check,
+    cli::Target,
+    errors::{
+        self, codes, source_map, ErrorType, FileLocation, LspError, ManagerError, Note, SourceId,
+    },
+    lexer::{
+        syntax::{
+            get_syntax_map, VARIABLE_ALLOWED_EXTRA_CHARS_INTERNAL,
+            VARIABLE_ALLOWED_EXTRA_CHARS_START,
+        },
+        tokens::{Token, TokenType},
+    },
+    lsp::{Analysis, Document, Position, TextEdit},
+    parser::{Function, Program},
+};
+
+// Start of a name from the lexer: (line, col)
+type Pos = (usize, usize);
+
+// What a name refers to, told apart by the file and place it is declared so
+// that it is the same in every file that uses it
+#[derive(Debug, Clone, PartialEq)]
+enum Symbol {
+    // Variables, parameters and loop counters
+    Local(PathBuf, Pos),
+    // Functions and methods
+    Function(PathBuf, Pos),
+    Struct(PathBuf, Pos),
+    // A field by its struct and name
+    Field(PathBuf, Pos, String),
+}
+impl Symbol {
+    fn describe(&self) -> &'static str {
+        match self {
+            Symbol::Local(..) => return "a variable",
+            Symbol::Function(..) => return "a function",
+            Symbol::Struct(..) => return "a struct",
+            Symbol::Field(..) => return "a field",
+        }
+    }
+}
+
+// A name written in a program
+struct Site {
+    loc: FileLocation,
+    name: String,
+    // None for built-in names
+    symbol: Option<Symbol>,
+    decl: bool,
+    // What the new name would refer to here
+    as_new: Option<Symbol>,
+    // What the old name refers to here
+    as_old: Option<Symbol>,
+}
+
+fn canonical(path: PathBuf) -> PathBuf {
+    match fs::canonicalize(&path) {
+        Ok(path) => return path,
+        Err(_) => return path,
+    }
+}
+
+fn place(loc: &FileLocation) -> Option<(PathBuf, Pos)> {
+    match loc {
+        FileLocation::Loc {
+            file,
+            start_line,
+            start_col,
+            ..
+        } => {
+            return Some((
+                canonical(source_map::source_path(*file)),
+                (*start_line, *start_col),
+            ))
+        }
+        _ => return None,
+    }
+}
+
+fn pos(loc: &FileLocation) -> Option<Pos> {
+    return place(loc).map(|(_, pos)| pos);
+}
+
+// Finds every name written in one file and what it refers to
+struct Finder<'a> {
+    source: SourceId,
+    doc: Document<'a>,
+    program: &'a Program,
+    aprogram: &'a AProgram,
+    old: &'a str,
+    new: &'a str,
+    scopes: Vec<Vec<(String, Symbol)>>,
+    sites: Vec<Site>,
+}
+impl<'a> Finder<'a> {
+    fn in_source(&self, loc: &FileLocation) -> bool {
+        match loc {
+            FileLocation::Loc { file, .. } => return *file == self.source,
+            _ => return false,
+        }
+    }
+
+    // Whether a location is where a name is written, as operators are
+    // turned into calls of methods at the operator
+    fn written(&self, loc: &FileLocation, name: &str) -> bool {
+        if !self.in_source(loc) {
+            return false;
+        }
+
+        let (line, col) = pos(loc).unwrap();
+        match self.doc.lines.get(line) {
+            Some(text) => {
+                let written = text.chars().skip(col - 1).take(name.chars().count());
+                return written.eq(name.chars());
+            }
+            None => return false,
+        }
+    }
+
+    fn global(&self, name: &str) -> Option<Symbol> {
+        if let Some(astruct) = self.aprogram.structs.iter().find(|s| s.name == name) {
+            let (path, pos) = place(&astruct.loc)?;
+            return Some(Symbol::Struct(path, pos));
+        }
+
+        if let Some(func) = self.aprogram.functions.iter().find(|f| f.name == name) {
+            let (path, pos) = place(&func.loc)?;
+            return Some(Symbol::Function(path, pos));
+        }
+
+        return None;
+    }
+
+    fn lookup(&self, name: &str) -> Option<Symbol> {
+        for scope in self.scopes.iter().rev() {
+            if let Some((_, symbol)) = scope.iter().rev().find(|(n, _)| n == name) {
+                return Some(symbol.clone());
+            }
+        }
+
+        return self.global(name);
+    }
+
+    fn member(astruct: &AStruct, name: &str) -> Option<Symbol> {
+        let (path, pos) = place(&astruct.loc)?;
+        if astruct.fields.contains_key(name) {
+            return Some(Symbol::Field(path, pos, name.to_string()));
+        }
+
+        let method = astruct.methods.get(name)?;
+        let (path, pos) = place(&method.loc)?;
+        return Some(Symbol::Function(path, pos));
+    }
+
+    // A name found by looking through the scopes
+    fn add_name(&mut self, loc: &FileLocation, name: &str, symbol: Option<Symbol>, decl: bool) {
+        if self.written(loc, name) {
+            self.sites.push(Site {
+                loc: loc.clone(),
+                name: name.to_string(),
+                symbol,
+                decl,
+                as_new: self.lookup(self.new),
+                as_old: self.lookup(self.old),
+            });
+        }
+    }
+
+    // A field or method of a struct
+    fn add_member(
+        &mut self,
+        loc: &FileLocation,
+        name: &str,
+        astruct: Option<&AStruct>,
+        decl: bool,
+    ) {
+        if self.written(loc, name) {
+            self.sites.push(Site {
+                loc: loc.clone(),
+                name: name.to_string(),
+                symbol: astruct.and_then(|astruct| Self::member(astruct, name)),
+                decl,
+                as_new: astruct.and_then(|astruct| Self::member(astruct, self.new)),
+                as_old: None,
+            });
+        }
+    }
+
+    fn declare(&mut self, loc: &FileLocation, name: &str) {
+        if let Some((path, pos)) = place(loc) {
+            let symbol = Symbol::Local(path, pos);
+            self.add_name(loc, name, Some(symbol.clone()), true);
+            self.scopes
+                .last_mut()
+                .unwrap()
+                .push((name.to_string(), symbol));
+        }
+    }
+
+    fn program(&mut self) {
+        for st in &self.program.structs {
+            if !self.in_source(&st.loc) {
+                continue;
+            }
+
+            let astruct = self.aprogram.structs.iter().find(|s| s.name == st.name);
+            let astruct = astruct.unwrap().clone();
+            self.add_name(&st.loc, &st.name, self.global(&st.name), true);
+
+            for field in &st.properties {
+                self.add_member(&field.loc, &field.identity, Some(&astruct), true);
+            }
+
+            for method in &st.methods {
+                self.add_member(&method.loc, &method.name, Some(&astruct), true);
+                if let Some(amethod) = astruct.methods.get(&method.name) {
+                    self.func(amethod, method);
+                }
+            }
+        }
+
+        for function in &self.program.functions {
+            if !self.in_source(&function.loc) {
+                continue;
+            }
+
+            self.add_name(
+                &function.loc,
+                &function.name,
+                self.global(&function.name),
+                true,
+            );
+            let afunc = self
+                .aprogram
+                .functions
+                .iter()
+                .find(|f| f.name == function.name);
+            self.func(&afunc.unwrap().clone(), function);
+        }
+
+        for import in &self.program.imports {
+            for (name, loc) in &import.objects {
+                self.add_name(loc, name, self.global(name), false);
+            }
+        }
+    }
+
+    fn func(&mut self, afunc: &AFunc, function: &Function) {
+        self.scopes.push(Vec::new());
+        for arg in &function.args {
+            self.declare(&arg.loc, &arg.identity);
+        }
+
+        if let AFuncBlock::TermsLang(block) = &afunc.block {
+            self.block(&block.borrow());
+        }
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, block: &ATermBlock) {
+        let terms = match block {
+            ATermBlock::A { terms } => terms,
+            ATermBlock::NotYetEvaluated(_) => panic!(),
+        };
+
+        self.scopes.push(Vec::new());
+        for term in terms {
+            self.term(term);
+        }
+        self.scopes.pop();
+    }
+
+    fn term(&mut self, term: &ATerm) {
+        match term {
+            ATerm::Print { value, .. } | ATerm::Return { value } | ATerm::Call { value } => {
+                self.expression(value)
+            }
+            ATerm::DeclareVar {
+                name, value, loc, ..
+            } => {
+                self.expression(value);
+                self.declare(loc, name);
+            }
+            ATerm::UpdateVar { value, var } => {
+                self.expression(value);
+                self.object(var);
+            }
+            ATerm::If {
+                conditional,
+                block,
+                else_block,
+            } => {
+                self.expression(conditional);
+                self.block(block);
+                self.block(else_block);
+            }
+            ATerm::Loop {
+                counter,
+                conditional,
+                block,
+                loc,
+            } => {
+                self.scopes.push(Vec::new());
+                self.declare(loc, counter);
+                self.expression(conditional);
+                self.block(block);
+                self.scopes.pop();
+            }
+            ATerm::Break(_) | ATerm::Continue(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &AOperandExpression) {
+        match &expression.value {
+            AOperandExpressionValue::Dot { left, right } => {
+                self.expression(left);
+                self.sub_object(right, &left.get_type());
+            }
+            AOperandExpressionValue::And { left, right }
+            | AOperandExpressionValue::Or { left, right } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            AOperandExpressionValue::Object(object) => self.object(object),
+            AOperandExpressionValue::Literal(_) => {}
+            AOperandExpressionValue::Create { args, .. } | AOperandExpressionValue::Array(args) => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, object: &AObject) {
+        if let AObjectType::Identity(id) = &object.kind {
+            self.add_name(&object.loc, id, self.lookup(id), false);
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub, &object._type);
+        }
+    }
+
+    // Fields and calls after the first name in an object
+    fn sub_object(&mut self, object: &AObject, parent: &Rc<RefCell<AType>>) {
+        match &object.kind {
+            AObjectType::Identity(id) => {
+                let astruct = match &*parent.borrow() {
+                    AType::StructObject(astruct) => Some(astruct.clone()),
+                    _ => None,
+                };
+                self.add_member(&object.loc, id, astruct.as_deref(), false);
+            }
+            AObjectType::Call(call) => {
+                for arg in &call.args {
+                    self.expression(arg);
+                }
+            }
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub, &object._type);
+        }
+    }
+
+    // Struct names left over in the tokens are types
+    fn types(&mut self, tokens: &[Token]) {
+        let found: Vec<Pos> = self
+            .sites
+            .iter()
+            .filter_map(|site| pos(&site.loc))
+            .collect();
+
+        for Token(token, loc) in tokens {
+            if let TokenType::Identity(name) = token {
+                let symbol = self.global(name);
+                let is_type = matches!(symbol, Some(Symbol::Struct(..)));
+                if is_type && pos(loc).is_some_and(|pos| !found.contains(&pos)) {
+                    self.add_name(loc, name, symbol, false);
+                }
+            }
+        }
+    }
+}
+
+fn find_sites(analysis: &Analysis, old: &str, new: &str) -> Vec<Site> {
+    let text = source_map::source_text(analysis.source);
+    let mut finder = Finder {
+        source: analysis.source,
+        doc: Document::new(&text),
+        program: analysis.program.as_ref().unwrap(),
+        aprogram: analysis.aprogram.as_ref().unwrap(),
+        old,
+        new,
+        scopes: Vec::new(),
+        sites: Vec::new(),
+    };
+
+    finder.program();
+    finder.types(&analysis.tokens);
+    return finder.sites;
+}
+
+fn invalid_name(new: &str) -> Option<String> {
+    let syntax_map = get_syntax_map();
+
+    let mut chars = new.chars();
+    let valid = match chars.next() {
+        Some(c) => c.is_ascii_alphabetic() || VARIABLE_ALLOWED_EXTRA_CHARS_START.contains(c),
+        None => false,
+    } && chars
+        .all(|c| c.is_alphanumeric() || VARIABLE_ALLOWED_EXTRA_CHARS_INTERNAL.contains(c));
+
+    if !valid {
+        return Some(format!("{new} is not a valid name."));
+    }
+
+    if syntax_map.keywords.contains_key(new)
+        || syntax_map.operators.contains_key(new)
+        || syntax_map.bools.contains_key(new)
+    {
+        return Some(format!("{new} is a keyword and can not be used as a name."));
+    }
+
+    if PREFIX_PROTECTED_NAMES.contains(&new) || new == nm::F_MAIN {
+        return Some(format!("{new} is a built-in name and can not be used."));
+    }
+
+    return None;
+}
+
+// Files that may use a struct or function: those in the directory of the
+// file renamed in, and the one it is declared in
+fn related_files(file: &Path, symbol: &Symbol) -> Vec<PathBuf> {
+    let dir = match file.parent() {
+        Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut files = Vec::new();
+    let _ = check::collect_files(&dir, &mut files);
+    if let Symbol::Function(path, _) | Symbol::Struct(path, _) = symbol {
+        files.push(path.clone());
+    }
+
+    let file = canonical(file.to_path_buf());
+    let mut related: Vec<PathBuf> = Vec::new();
+    for path in files.into_iter().map(canonical) {
+        if path != file && !related.contains(&path) {
+            related.push(path);
+        }
+    }
+
+    return related;
+}
+
+// Names to change in one file
+pub struct FileRename {
+    pub path: PathBuf,
+    pub text: String,
+    // Where each use of the old name starts
+    pub starts: Vec<Pos>,
+}
+
+pub struct Rename {
+    pub old: String,
+    pub new: String,
+    pub files: Vec<FileRename>,
+}
+
+// Rename what the name at a line and column refers to in every file that
+// uses it, or the reason it can not be
+pub fn rename(
+    file: &Path,
+    text: &str,
+    line: usize,
+    col: usize,
+    new: &str,
+) -> Result<Rename, ErrorType> {
+    let mut analysis = super::analyse(file, text);
+    let at = FileLocation::Loc {
+        file: analysis.source,
+        start_line: line,
+        end_line: line,
+        start_col: col,
+        end_col: col,
+    };
+
+    // The new name is checked before any other file is read
+    if let Some(msg) = invalid_name(new) {
+        return Err(ErrorType::Lsp(LspError::new(msg, at, codes::S0002)));
+    }
+
+    if analysis.aprogram.is_none() {
+        return Err(analysis.errors.remove(0));
+    }
+
+    let target = find_sites(&analysis, "", "")
+        .into_iter()
+        .find(|site| match site.loc {
+            FileLocation::Loc {
+                start_line,
+                start_col,
+                ..
+            } => {
+                start_line == line
+                    && start_col <= col
+                    && col <= start_col + site.name.chars().count()
+            }
+            _ => false,
+        });
+
+    let (old, symbol) = match target {
+        Some(Site {
+            name,
+            symbol: Some(symbol),
+            ..
+        }) if name != nm::F_MAIN && !PREFIX_PROTECTED_NAMES.contains(&name.as_str()) => {
+            (name, symbol)
+        }
+        Some(site) => {
+            return Err(ErrorType::Lsp(LspError::new(
+                format!("{} is built in and can not be renamed.", site.name),
+                site.loc,
+                codes::S0004,
+            )))
+        }
+        None => {
+            return Err(ErrorType::Lsp(LspError::new(
+                format!("No name to rename here."),
+                at,
+                codes::S0004,
+            )))
+        }
+    };
+
+    // Keeping the same name changes nothing
+    if old == new {
+        return Ok(Rename {
+            old,
+            new: new.to_string(),
+            files: Vec::new(),
+        });
+    }
+
+    let mut analyses = vec![analysis];
+    if !matches!(symbol, Symbol::Local(..)) {
+        for path in related_files(file, &symbol) {
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(_) => continue,
+            };
+
+            let analysis = super::analyse(&path, &text);
+            if analysis.aprogram.is_some() {
+                analyses.push(analysis);
+                continue;
+            }
+
+            // Files that do not check are only left out when they can not
+            // be using the name
+            if text.contains(&old) {
+                return Err(ErrorType::Lsp(LspError::new(
+                    format!("Could not check {}, which may use {old}.", path.display()),
+                    FileLocation::None,
+                    codes::S0005,
+                )));
+            }
+        }
+    }
+
+    let sites: Vec<Site> = analyses
+        .iter()
+        .flat_map(|analysis| find_sites(analysis, &old, new))
+        .collect();
+    if let Some(err) = conflict(&sites, &symbol, &old, new) {
+        return Err(ErrorType::Lsp(err));
+    }
+
+    let mut files: Vec<FileRename> = Vec::new();
+    let mut paths: HashMap<PathBuf, usize> = HashMap::new();
+    for site in sites
+        .iter()
+        .filter(|site| site.symbol.as_ref() == Some(&symbol))
+    {
+        let (path, pos) = place(&site.loc).unwrap();
+        let idx = *paths.entry(path.clone()).or_insert_with(|| {
+            let text = match &site.loc {
+                FileLocation::Loc { file, .. } => source_map::source_text(*file),
+                _ => panic!(),
+            };
+            files.push(FileRename {
+                path,
+                text,
+                starts: Vec::new(),
+            });
+            files.len() - 1
+        });
+
+        if !files[idx].starts.contains(&pos) {
+            files[idx].starts.push(pos);
+        }
+    }
+
+    return Ok(Rename {
+        old,
+        new: new.to_string(),
+        files,
+    });
+}
+
+// The first place the rename would change what a name refers to
+fn conflict(sites: &[Site], symbol: &Symbol, old: &str, new: &str) -> Option<LspError> {
+    let declared = |other: &Symbol| -> Vec<Note> {
+        match sites
+            .iter()
+            .find(|site| site.decl && site.symbol.as_ref() == Some(other))
+        {
+            Some(site) => return vec![Note(format!("{new} declared here"), site.loc.clone())],
+            None => return Vec::new(),
+        }
+    };
+
+    for site in sites {
+        if site.symbol.as_ref() == Some(symbol) {
+            if let Some(other) = site.as_new.as_ref().filter(|other| *other != symbol) {
+                return Some(LspError(
+                    format!("{new} already names {} here.", other.describe()),
+                    site.loc.clone(),
+                    codes::S0003,
+                    declared(other),
+                ));
+            }
+        } else if site.name == new && site.as_old.as_ref() == Some(symbol) {
+            let notes = match &site.symbol {
+                Some(other) => declared(other),
+                None => Vec::new(),
+            };
+            return Some(LspError(
+                format!("Renaming {old} to {new} would change what {new} refers to here."),
+                site.loc.clone(),
+                codes::S0003,
+                notes,
+            ));
+        }
+    }
+
+    return None;
+}
+
+// `textDocument/rename`
+pub fn rename_request(file: &Path, text: &str, position: Position, new: &str) -> String {
+    let col = Document::new(text).lexer_col(position);
+    let rename = match rename(file, text, position.line, col, new) {
+        Ok(rename) => rename,
+        Err(err) => return super::response(None, &[err]),
+    };
+
+    let mut changes = Vec::new();
+    for file in &rename.files {
+        let doc = Document::new(&file.text);
+        let edits: Vec<String> = file
+            .starts
+            .iter()
+            .map(|(line, col)| {
+                let edit = TextEdit {
+                    range: doc.range(*line, *col, &rename.old),
+                    new_text: rename.new.clone(),
+                };
+                edit.json()
+            })
+            .collect();
+
+        changes.push(format!(
+            "{}:[{}]",
+            errors::json_string(&super::uri(&file.path)),
+            edits.join(",")
+        ));
+    }
+
+    let result = format!("{{\"changes\":{{{}}}}}", changes.join(","));
+    return super::response(Some(result), &[]);
+}
+
+// Replace each use of the old name in a file's text
+fn apply(file: &FileRename, old: &str, new: &str) -> String {
+    let mut lines: Vec<String> = file.text.split('\n').map(str::to_string).collect();
+
+    let mut starts = file.starts.clone();
+    starts.sort();
+    for (line, col) in starts.into_iter().rev() {
+        let chars: Vec<char> = lines[line].chars().collect();
+        let before: String = chars[..col - 1].iter().collect();
+        let after: String = chars[col - 1 + old.chars().count()..].iter().collect();
+        lines[line] = format!("{before}{new}{after}");
+    }
+
+    return lines.join("\n");
+}
+
+// `termslang rename`, which rewrites the files. Returns the exit status.
+pub fn rename_files(target: &Target, new: &str) -> i32 {
+    let text = match fs::read_to_string(&target.file) {
+        Ok(text) => text,
+        Err(err) => {
+            let err = ManagerError::new(
+                format!("Could not open program file | {err}"),
+                FileLocation::None,
+                codes::M0001,
+            );
+            eprintln!("{}", err.report());
+            return errors::EXIT_MANAGER;
+        }
+    };
+
+    // Lines from the lexer count from zero
+    let rename = match rename(&target.file, &text, target.line - 1, target.col, new) {
+        Ok(rename) => rename,
+        Err(err) => {
+            let (msg, status) = match err {
+                ErrorType::Lexer(err) => (err.report(), errors::EXIT_LEXER),
+                ErrorType::Parser(err) => (err.report(), errors::EXIT_PARSER),
+                ErrorType::AParser(err) => (err.report(), errors::EXIT_APARSER),
+                ErrorType::Lsp(err) => (err.report(), errors::EXIT_MANAGER),
+                ErrorType::Manager(err) => (err.report(), errors::EXIT_MANAGER),
+            };
+            eprintln!("{msg}");
+            return status;
+        }
+    };
+
+    let mut count = 0;
+    for file in &rename.files {
+        if let Err(err) = fs::write(&file.path, apply(file, &rename.old, &rename.new)) {
+            let err = ManagerError::new(
+                format!(
+                    "Could not write to program file {} | {err}",
+                    file.path.display()
+                ),
+                FileLocation::None,
+                codes::M0002,
+            );
+            eprintln!("{}", err.report());
+            return errors::EXIT_MANAGER;
+        }
+        count += file.starts.len();
+    }
+
+    println!(
+        "Renamed {count} use{} of {} to {} in {} file{}.",
+        if count == 1 { "" } else { "s" },
+        rename.old,
+        rename.new,
+        rename.files.len(),
+        if rename.files.len() == 1 { "" } else { "s" }
+    );
+    return 0;
+}
@@ -0,0 +1,447 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    active_parser::{
+        names::{self as nm, PREFIX_PROTECTED_NAMES},
+        AFunc, AFuncBlock, AObject, AObjectType, AOperandExpression, AOperandExpressionValue,
+        AProgram, ATerm, ATermBlock, AType,
+    },
+    errors::{ErrorType, FileLocation, SourceId},
+    lexer::tokens::{KeyWord, Operator, Token, TokenType},
+    lsp::{Analysis, Document},
+    parser::Program,
+};
+
+// Legend editors are given, in the order tokens refer to it by
+const TOKEN_TYPES: [&str; 6] = [
+    "type",
+    "function",
+    "method",
+    "property",
+    "parameter",
+    "variable",
+];
+const TOKEN_MODIFIERS: [&str; 3] = ["declaration", "defaultLibrary", "loopCounter"];
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Type,
+    Function,
+    Method,
+    Property,
+    Parameter,
+    Variable,
+}
+
+// Bits of the modifiers, from TOKEN_MODIFIERS
+const DECLARATION: u32 = 1;
+const DEFAULT_LIBRARY: u32 = 2;
+const LOOP_COUNTER: u32 = 4;
+
+#[derive(Clone, Copy)]
+struct Class {
+    kind: Kind,
+    modifiers: u32,
+}
+impl Class {
+    fn new(kind: Kind, modifiers: u32) -> Self {
+        Class { kind, modifiers }
+    }
+}
+
+// Start of a token from the lexer: (line, col)
+type Pos = (usize, usize);
+
+fn start(loc: &FileLocation) -> Option<Pos> {
+    match loc {
+        FileLocation::Loc {
+            start_line,
+            start_col,
+            ..
+        } => return Some((*start_line, *start_col)),
+        _ => return None,
+    }
+}
+
+fn built_in(name: &str) -> u32 {
+    match PREFIX_PROTECTED_NAMES.contains(&name) {
+        true => return DEFAULT_LIBRARY,
+        false => return 0,
+    }
+}
+
+fn is_operator(tokens: &[Token], idx: usize, operator: Operator) -> bool {
+    match tokens.get(idx) {
+        Some(Token(TokenType::Operator(op), _)) => return *op == operator,
+        _ => return false,
+    }
+}
+
+// Index of the token after a type such as `int[][]`
+fn skip_type(tokens: &[Token], mut idx: usize) -> usize {
+    if let Some(Token(TokenType::Identity(_), _)) = tokens.get(idx) {
+        idx += 1;
+        while is_operator(tokens, idx, Operator::OpenBracket)
+            && is_operator(tokens, idx + 1, Operator::CloseBracket)
+        {
+            idx += 2;
+        }
+    }
+
+    return idx;
+}
+
+fn declare(classes: &mut HashMap<Pos, Class>, tokens: &[Token], idx: usize, class: Class) {
+    if let Some(Token(TokenType::Identity(_), loc)) = tokens.get(idx) {
+        if let Some(pos) = start(loc) {
+            classes.insert(pos, Class::new(class.kind, class.modifiers | DECLARATION));
+        }
+    }
+}
+
+// Names declared in the token stream, which are found from the keywords
+// before them
+fn declarations(tokens: &[Token], classes: &mut HashMap<Pos, Class>) {
+    let mut depth = 0;
+    // Depth of the body of the struct being read
+    let mut struct_depth: Option<usize> = None;
+
+    for idx in 0..tokens.len() {
+        match &tokens[idx].0 {
+            TokenType::Operator(Operator::OpenBlock) => depth += 1,
+            TokenType::Operator(Operator::CloseBlock) => {
+                depth -= 1;
+                if struct_depth.is_some_and(|struct_depth| struct_depth > depth) {
+                    struct_depth = None;
+                }
+            }
+            TokenType::KeyWord(KeyWord::Struct) => {
+                declare(classes, tokens, idx + 1, Class::new(Kind::Type, 0));
+                struct_depth = Some(depth + 1);
+            }
+            TokenType::KeyWord(KeyWord::Var) => {
+                let kind = match struct_depth == Some(depth) {
+                    true => Kind::Property,
+                    false => Kind::Variable,
+                };
+                declare(
+                    classes,
+                    tokens,
+                    skip_type(tokens, idx + 1),
+                    Class::new(kind, 0),
+                );
+            }
+            TokenType::KeyWord(KeyWord::Func) => {
+                let kind = match struct_depth {
+                    Some(_) => Kind::Method,
+                    None => Kind::Function,
+                };
+                let name = skip_type(tokens, idx + 1);
+                declare(classes, tokens, name, Class::new(kind, 0));
+
+                if !is_operator(tokens, name + 1, Operator::Colon) {
+                    continue;
+                }
+
+                let mut arg = name + 2;
+                loop {
+                    let name = skip_type(tokens, arg);
+                    declare(classes, tokens, name, Class::new(Kind::Parameter, 0));
+
+                    if !is_operator(tokens, name + 1, Operator::Comma) {
+                        break;
+                    }
+                    arg = name + 2;
+                }
+            }
+            TokenType::KeyWord(KeyWord::Loop) => {
+                let class = Class::new(Kind::Variable, LOOP_COUNTER);
+                declare(classes, tokens, idx + 1, class);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Finds what each name used in a program refers to
+struct Resolver<'a> {
+    source: SourceId,
+    scopes: Vec<Vec<(String, Class)>>,
+    classes: &'a mut HashMap<Pos, Class>,
+}
+impl<'a> Resolver<'a> {
+    fn in_source(&self, loc: &FileLocation) -> bool {
+        match loc {
+            FileLocation::Loc { file, .. } => return *file == self.source,
+            _ => return false,
+        }
+    }
+
+    fn mark(&mut self, loc: &FileLocation, class: Class) {
+        if !self.in_source(loc) {
+            return;
+        }
+
+        if let Some(pos) = start(loc) {
+            self.classes.entry(pos).or_insert(class);
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<Class> {
+        for scope in self.scopes.iter().rev() {
+            if let Some((_, class)) = scope.iter().rev().find(|(n, _)| n == name) {
+                return Some(*class);
+            }
+        }
+
+        return None;
+    }
+
+    fn func(&mut self, func: &AFunc, this: bool) {
+        let mut scope: Vec<(String, Class)> = func
+            .args
+            .iter()
+            .map(|arg| (arg.name.clone(), Class::new(Kind::Parameter, 0)))
+            .collect();
+        if this {
+            scope.push((
+                nm::THIS.to_string(),
+                Class::new(Kind::Parameter, DEFAULT_LIBRARY),
+            ));
+        }
+
+        if let AFuncBlock::TermsLang(block) = &func.block {
+            self.scopes.push(scope);
+            self.block(&block.borrow());
+            self.scopes.pop();
+        }
+    }
+
+    fn block(&mut self, block: &ATermBlock) {
+        let terms = match block {
+            ATermBlock::A { terms } => terms,
+            ATermBlock::NotYetEvaluated(_) => panic!(),
+        };
+
+        self.scopes.push(Vec::new());
+        for term in terms {
+            self.term(term);
+        }
+        self.scopes.pop();
+    }
+
+    fn term(&mut self, term: &ATerm) {
+        match term {
+            ATerm::Print { value, .. } | ATerm::Return { value } | ATerm::Call { value } => {
+                self.expression(value)
+            }
+            ATerm::DeclareVar { name, value, .. } => {
+                self.expression(value);
+
+                let class = Class::new(Kind::Variable, 0);
+                self.scopes.last_mut().unwrap().push((name.clone(), class));
+            }
+            ATerm::UpdateVar { value, var } => {
+                self.expression(value);
+                self.object(var);
+            }
+            ATerm::If {
+                conditional,
+                block,
+                else_block,
+            } => {
+                self.expression(conditional);
+                self.block(block);
+                self.block(else_block);
+            }
+            ATerm::Loop {
+                counter,
+                conditional,
+                block,
+                ..
+            } => {
+                let class = Class::new(Kind::Variable, LOOP_COUNTER);
+                self.scopes.push(vec![(counter.clone(), class)]);
+                self.expression(conditional);
+                self.block(block);
+                self.scopes.pop();
+            }
+            ATerm::Break(_) | ATerm::Continue(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &AOperandExpression) {
+        match &expression.value {
+            AOperandExpressionValue::Dot { left, right } => {
+                self.expression(left);
+                self.sub_object(right);
+            }
+            AOperandExpressionValue::And { left, right }
+            | AOperandExpressionValue::Or { left, right } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            AOperandExpressionValue::Object(object) => self.object(object),
+            AOperandExpressionValue::Literal(_) => {}
+            AOperandExpressionValue::Create { args, .. } | AOperandExpressionValue::Array(args) => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, object: &AObject) {
+        if let AObjectType::Identity(id) = &object.kind {
+            let class = match self.find(id) {
+                Some(class) => class,
+                None => match &*object._type.borrow() {
+                    AType::FuncDefRef(_) => Class::new(Kind::Function, built_in(id)),
+                    AType::StructDefRef(_) => Class::new(Kind::Type, built_in(id)),
+                    _ => Class::new(Kind::Variable, built_in(id)),
+                },
+            };
+            self.mark(&object.loc, class);
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub);
+        }
+    }
+
+    // Fields and calls after the first name in an object
+    fn sub_object(&mut self, object: &AObject) {
+        match &object.kind {
+            AObjectType::Identity(id) => {
+                let kind = match &*object._type.borrow() {
+                    AType::FuncDefRef(_) => Kind::Method,
+                    _ => Kind::Property,
+                };
+                self.mark(&object.loc, Class::new(kind, built_in(id)));
+            }
+            AObjectType::Call(call) => {
+                for arg in &call.args {
+                    self.expression(arg);
+                }
+            }
+        }
+
+        if let Some(sub) = &object.sub {
+            self.sub_object(sub);
+        }
+    }
+}
+
+fn resolve(program: &AProgram, source: SourceId, classes: &mut HashMap<Pos, Class>) {
+    let mut resolver = Resolver {
+        source,
+        scopes: Vec::new(),
+        classes,
+    };
+
+    for func in &program.functions {
+        if resolver.in_source(&func.loc) {
+            resolver.func(func, false);
+        }
+    }
+
+    for astruct in &program.structs {
+        if resolver.in_source(&astruct.loc) {
+            for method in astruct.methods.values() {
+                resolver.func(method, true);
+            }
+        }
+    }
+}
+
+// Names left over are types, or the structs and functions named by imports
+fn globals(tokens: &[Token], program: Option<&Program>, classes: &mut HashMap<Pos, Class>) {
+    for token in tokens {
+        let (name, pos) = match token {
+            Token(TokenType::Identity(name), loc) => match start(loc) {
+                Some(pos) if !classes.contains_key(&pos) => (name, pos),
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let is_struct = |program: &Program| program.structs.iter().any(|s| s.name == *name);
+        let is_function = |program: &Program| program.functions.iter().any(|f| f.name == *name);
+
+        if PREFIX_PROTECTED_NAMES.contains(&name.as_str()) && !name.starts_with('@') {
+            classes.insert(pos, Class::new(Kind::Type, DEFAULT_LIBRARY));
+        } else if program.is_some_and(is_struct) {
+            classes.insert(pos, Class::new(Kind::Type, 0));
+        } else if program.is_some_and(is_function) {
+            classes.insert(pos, Class::new(Kind::Function, 0));
+        }
+    }
+}
+
+// `textDocument/semanticTokens/full`. Declarations are found even when the
+// program does not parse, and the names it uses once it is checked.
+fn tokens(analysis: &Analysis, text: &str) -> Option<String> {
+    if let Some(ErrorType::Lexer(_)) = analysis.errors.first() {
+        return None;
+    }
+
+    let mut classes = HashMap::new();
+    declarations(&analysis.tokens, &mut classes);
+    if let Some(aprogram) = &analysis.aprogram {
+        resolve(aprogram, analysis.source, &mut classes);
+    }
+    globals(&analysis.tokens, analysis.program.as_ref(), &mut classes);
+
+    let doc = Document::new(text);
+    let mut data = Vec::new();
+    let (mut last_line, mut last_char) = (0, 0);
+
+    // Tokens come from the lexer in order
+    for token in &analysis.tokens {
+        let (name, loc) = match token {
+            Token(TokenType::Identity(name), loc) => (name, loc),
+            _ => continue,
+        };
+        let class = match start(loc).and_then(|pos| classes.get(&pos)) {
+            Some(class) => class,
+            None => continue,
+        };
+        let range = doc.name_range(loc, name).unwrap();
+
+        // Each token is placed relative to the one before it
+        let delta_char = match range.start.line == last_line {
+            true => range.start.character - last_char,
+            false => range.start.character,
+        };
+        data.push(format!(
+            "{},{delta_char},{},{},{}",
+            range.start.line - last_line,
+            range.end.character - range.start.character,
+            class.kind as usize,
+            class.modifiers
+        ));
+
+        (last_line, last_char) = (range.start.line, range.start.character);
+    }
+
+    return Some(format!("{{\"data\":[{}]}}", data.join(",")));
+}
+
+// The legend as `semanticTokensProvider.legend` expects it
+pub fn legend() -> String {
+    let quote = |names: &[&str]| -> String {
+        let names: Vec<String> = names.iter().map(|name| format!("\"{name}\"")).collect();
+        return names.join(",");
+    };
+
+    return format!(
+        "{{\"tokenTypes\":[{}],\"tokenModifiers\":[{}]}}",
+        quote(&TOKEN_TYPES),
+        quote(&TOKEN_MODIFIERS)
+    );
+}
+
+pub fn semantic_tokens(file: &Path, text: &str) -> String {
+    let analysis = super::analyse(file, text);
+    return super::response(tokens(&analysis, text), &analysis.errors);
+}
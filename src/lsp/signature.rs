@@ -0,0 +1,529 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use crate::{
+    active_parser::{
+        names as nm, AFunc, AFuncBlock, AObject, AObjectType, AOperandExpression,
+        AOperandExpressionValue, AProgram, AStruct, ATerm, ATermBlock, AType,
+    },
+    errors::{json_string, FileLocation, SourceId},
+    lexer::tokens::{Operator, Token, TokenType},
+    lsp::{Document, Position},
+};
+
+// Start of a token from the lexer: (line, col)
+type Pos = (usize, usize);
+
+// A call written in the document
+pub struct Call {
+    pub func: Rc<AFunc>,
+    // Index of the opening parenthesis in the tokens
+    pub open: usize,
+    // Index of the closing parenthesis
+    pub close: usize,
+    // Index of the first token of each argument
+    pub args: Vec<usize>,
+    // Index of each comma between the arguments
+    pub commas: Vec<usize>,
+}
+
+// Finds the calls in the functions of a document
+struct Calls<'a> {
+    source: SourceId,
+    tokens: &'a [Token],
+    calls: Vec<Call>,
+}
+impl<'a> Calls<'a> {
+    fn in_source(&self, loc: &FileLocation) -> bool {
+        match loc {
+            FileLocation::Loc { file, .. } => return *file == self.source,
+            _ => return false,
+        }
+    }
+
+    // Operators are turned into calls located at the operator, so only
+    // calls located at a parenthesis were written as calls
+    fn add(&mut self, func: Rc<AFunc>, loc: &FileLocation) {
+        if !self.in_source(loc) {
+            return;
+        }
+
+        let open = match self.tokens.iter().position(|token| token.1 == *loc) {
+            Some(idx) => idx,
+            None => return,
+        };
+        // `$(...) Type` is located at the `$`
+        let open = match self.tokens[open].0 {
+            TokenType::Operator(Operator::New) => open + 1,
+            _ => open,
+        };
+        match self.tokens.get(open) {
+            Some(Token(TokenType::Operator(Operator::OpenParen), _)) => {}
+            _ => return,
+        }
+
+        let mut depth = 0;
+        let mut args = Vec::new();
+        let mut commas = Vec::new();
+        for idx in open..self.tokens.len() {
+            match self.tokens[idx].0 {
+                TokenType::Operator(
+                    Operator::OpenParen | Operator::OpenBracket | Operator::OpenBlock,
+                ) => depth += 1,
+                TokenType::Operator(
+                    Operator::CloseParen | Operator::CloseBracket | Operator::CloseBlock,
+                ) => depth -= 1,
+                TokenType::Operator(Operator::Comma) if depth == 1 => {
+                    commas.push(idx);
+                    args.push(idx + 1);
+                    continue;
+                }
+                _ => {}
+            }
+
+            if depth == 0 {
+                if idx > open + 1 {
+                    args.insert(0, open + 1);
+                }
+                self.calls.push(Call {
+                    func,
+                    open,
+                    close: idx,
+                    args,
+                    commas,
+                });
+                return;
+            }
+        }
+    }
+
+    fn func(&mut self, func: &AFunc) {
+        if let AFuncBlock::TermsLang(block) = &func.block {
+            self.block(&block.borrow());
+        }
+    }
+
+    fn block(&mut self, block: &ATermBlock) {
+        let terms = match block {
+            ATermBlock::A { terms } => terms,
+            ATermBlock::NotYetEvaluated(_) => panic!(),
+        };
+
+        for term in terms {
+            self.term(term);
+        }
+    }
+
+    fn term(&mut self, term: &ATerm) {
+        match term {
+            ATerm::Print { value, .. }
+            | ATerm::Return { value }
+            | ATerm::Call { value }
+            | ATerm::DeclareVar { value, .. } => self.expression(value),
+            ATerm::UpdateVar { value, var } => {
+                self.expression(value);
+                self.object(var);
+            }
+            ATerm::If {
+                conditional,
+                block,
+                else_block,
+            } => {
+                self.expression(conditional);
+                self.block(block);
+                self.block(else_block);
+            }
+            ATerm::Loop {
+                conditional, block, ..
+            } => {
+                self.expression(conditional);
+                self.block(block);
+            }
+            ATerm::Break(_) | ATerm::Continue(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &AOperandExpression) {
+        match &expression.value {
+            AOperandExpressionValue::Dot { left, right } => {
+                self.expression(left);
+                self.object(right);
+            }
+            AOperandExpressionValue::And { left, right }
+            | AOperandExpressionValue::Or { left, right } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            AOperandExpressionValue::Object(object) => self.object(object),
+            AOperandExpressionValue::Literal(_) => {}
+            AOperandExpressionValue::Create { _type, args } => {
+                if let AType::StructDefRef(astruct) = &*_type.borrow() {
+                    if let Some(new) = astruct.methods.get(nm::F_NEW) {
+                        self.add(new.clone(), &expression.loc);
+                    }
+                }
+
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+            AOperandExpressionValue::Array(args) => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+        }
+    }
+
+    // A call is the object after a function in a chain such as `a.b.()`
+    fn object(&mut self, object: &AObject) {
+        let mut current = object;
+        while let Some(sub) = &current.sub {
+            if let AObjectType::Call(call) = &sub.kind {
+                if let AType::FuncDefRef(func) = &*current._type.borrow() {
+                    self.add(func.clone(), &sub.loc);
+                }
+
+                for arg in &call.args {
+                    self.expression(arg);
+                }
+            }
+            current = sub;
+        }
+    }
+}
+
+// Calls written in the document, in no particular order
+pub fn calls(program: &AProgram, source: SourceId, tokens: &[Token]) -> Vec<Call> {
+    let mut calls = Calls {
+        source,
+        tokens,
+        calls: Vec::new(),
+    };
+
+    for func in &program.functions {
+        if calls.in_source(&func.loc) {
+            calls.func(func);
+        }
+    }
+
+    for astruct in &program.structs {
+        if calls.in_source(&astruct.loc) {
+            for method in astruct.methods.values() {
+                calls.func(method);
+            }
+        }
+    }
+
+    return calls.calls;
+}
+
+// Name as it is written, without the prefix given to names from imported
+// files
+pub fn unprefixed(name: &str) -> &str {
+    match name.rfind("::") {
+        Some(idx) => return &name[idx + 2..],
+        None => return name,
+    }
+}
+
+fn type_name(_type: &AType) -> String {
+    match _type {
+        AType::ArrayObject(_type) => return format!("{}[]", type_name(&_type.borrow())),
+        AType::StructObject(astruct) | AType::StructDefRef(astruct) => {
+            return unprefixed(&astruct.name).to_string()
+        }
+        _ => return format!("{_type}"),
+    }
+}
+
+// A function as it is written after `func`, such as `int double: int n`,
+// with the range of each parameter in the label
+fn label(func: &AFunc) -> (String, Vec<(usize, usize)>) {
+    let mut label = format!(
+        "{} {}",
+        type_name(&func.returntype.borrow()),
+        unprefixed(&func.name)
+    );
+    let mut params = Vec::new();
+
+    for (idx, arg) in func.args.iter().enumerate() {
+        label += match idx {
+            0 => ": ",
+            _ => ", ",
+        };
+
+        let start = label.encode_utf16().count();
+        label += &type_name(&arg._type.borrow());
+        if !arg.name.is_empty() {
+            label += &format!(" {}", unprefixed(&arg.name));
+        }
+        params.push((start, label.encode_utf16().count()));
+    }
+
+    return (label, params);
+}
+
+// Functions of a document, with the struct of each method
+fn funcs(program: &AProgram, source: SourceId) -> Vec<(Rc<AFunc>, Option<Rc<AStruct>>)> {
+    let in_source = |loc: &FileLocation| match loc {
+        FileLocation::Loc { file, .. } => *file == source,
+        _ => false,
+    };
+
+    let mut funcs: Vec<(Rc<AFunc>, Option<Rc<AStruct>>)> = program
+        .functions
+        .iter()
+        .filter(|func| in_source(&func.loc))
+        .map(|func| (func.clone(), None))
+        .collect();
+    for astruct in &program.structs {
+        if in_source(&astruct.loc) {
+            for method in astruct.methods.values() {
+                funcs.push((method.clone(), Some(astruct.clone())));
+            }
+        }
+    }
+
+    return funcs;
+}
+
+// Type of a variable declared in a block
+fn declared(block: &ATermBlock, name: &str) -> Option<Rc<RefCell<AType>>> {
+    let terms = match block {
+        ATermBlock::A { terms } => terms,
+        ATermBlock::NotYetEvaluated(_) => panic!(),
+    };
+
+    for term in terms {
+        let found = match term {
+            ATerm::DeclareVar {
+                name: var,
+                _vartype,
+                ..
+            } if unprefixed(var) == name => Some(_vartype.clone()),
+            ATerm::If {
+                block, else_block, ..
+            } => declared(block, name).or_else(|| declared(else_block, name)),
+            ATerm::Loop { block, .. } => declared(block, name),
+            _ => None,
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    return None;
+}
+
+// Function called by `name.(` or `receiver.name.(` in a document that does
+// not parse. The receiver is looked up among the variables of the function
+// the call is written in.
+fn find_func(
+    program: &AProgram,
+    source: SourceId,
+    tokens: &[Token],
+    open: usize,
+) -> Option<Rc<AFunc>> {
+    let identity = |idx: Option<usize>| match idx.and_then(|idx| tokens.get(idx)) {
+        Some(Token(TokenType::Identity(id), _)) => Some(id.as_str()),
+        _ => None,
+    };
+    let dot = |idx: Option<usize>| match idx.and_then(|idx| tokens.get(idx)) {
+        Some(Token(TokenType::Operator(Operator::Dot), _)) => true,
+        _ => false,
+    };
+
+    if !dot(open.checked_sub(1)) {
+        return None;
+    }
+    let name = identity(open.checked_sub(2))?;
+
+    if !dot(open.checked_sub(3)) {
+        let funcs = program.functions.iter();
+        return funcs
+            .clone()
+            .find(|func| func.name == name)
+            .or_else(|| funcs.clone().find(|func| unprefixed(&func.name) == name))
+            .cloned();
+    }
+
+    let receiver = identity(open.checked_sub(4))?;
+    if dot(open.checked_sub(5)) {
+        return None;
+    }
+
+    let at = tokens[open].1.start();
+    let (func, astruct) = funcs(program, source)
+        .into_iter()
+        .filter(|(func, _)| func.loc.start() < at)
+        .max_by_key(|(func, _)| func.loc.start())?;
+
+    let _type = match (receiver, astruct) {
+        (nm::THIS, Some(astruct)) => RefCell::new(AType::StructObject(astruct)).into(),
+        _ => {
+            let arg = func
+                .args
+                .iter()
+                .find(|arg| unprefixed(&arg.name) == receiver);
+            match (arg, &func.block) {
+                (Some(arg), _) => arg._type.clone(),
+                (None, AFuncBlock::TermsLang(block)) => declared(&block.borrow(), receiver)?,
+                _ => return None,
+            }
+        }
+    };
+
+    match &*_type.borrow() {
+        AType::StructObject(astruct) | AType::StructDefRef(astruct) => {
+            return astruct.methods.get(name).cloned()
+        }
+        _ => return None,
+    };
+}
+
+// Text with the characters from `from` up to `to` replaced by spaces, which
+// keeps the positions of everything else
+fn blank(text: &str, from: Pos, to: Pos) -> String {
+    let mut blanked = String::with_capacity(text.len());
+    for (line, text) in text.split_inclusive('\n').enumerate() {
+        for (idx, c) in text.chars().enumerate() {
+            let at = (line, idx + 1);
+            match from <= at && at < to && c != '\n' {
+                true => blanked.push(' '),
+                false => blanked.push(c),
+            }
+        }
+    }
+
+    return blanked;
+}
+
+// The call being typed at a position in a document that does not parse.
+// The statement it is in is left out so the rest of the document can be
+// analysed, and the function is found from the tokens before the
+// parenthesis. Returns the function and the number of commas before the
+// position.
+fn typed_call(file: &Path, text: &str, tokens: &[Token], at: Pos) -> Option<(Rc<AFunc>, usize)> {
+    let pos = |idx: usize| -> Pos { tokens[idx].1.start() };
+    let ends_statement = |idx: usize| {
+        matches!(
+            tokens[idx].0,
+            TokenType::Terminate | TokenType::Operator(Operator::OpenBlock | Operator::CloseBlock)
+        )
+    };
+
+    // Innermost parenthesis left open before the position
+    let mut opened = Vec::new();
+    for idx in (0..tokens.len()).take_while(|idx| pos(*idx) < at) {
+        match tokens[idx].0 {
+            TokenType::Operator(Operator::OpenParen | Operator::OpenBracket) => opened.push(idx),
+            TokenType::Operator(Operator::CloseParen | Operator::CloseBracket) => {
+                opened.pop();
+            }
+            _ => {}
+        }
+    }
+    let open = *opened
+        .iter()
+        .rev()
+        .find(|idx| tokens[**idx].0 == TokenType::Operator(Operator::OpenParen))?;
+
+    let mut depth = 0;
+    let mut commas = 0;
+    for idx in (open..tokens.len()).take_while(|idx| pos(*idx) < at) {
+        match tokens[idx].0 {
+            TokenType::Operator(Operator::OpenParen | Operator::OpenBracket) => depth += 1,
+            TokenType::Operator(Operator::CloseParen | Operator::CloseBracket) => depth -= 1,
+            TokenType::Operator(Operator::Comma) if depth == 1 => commas += 1,
+            _ => {}
+        }
+    }
+
+    // The statement runs from the end of the one before it to its
+    // terminator. Without a terminator on the line yet it may end at the
+    // end of the line, or take the next statement with it.
+    let start = match (0..open).rev().find(|idx| ends_statement(*idx)) {
+        Some(idx) => after(&tokens[idx].1),
+        None => (0, 0),
+    };
+    let next = (open..tokens.len()).find(|idx| ends_statement(*idx));
+    let through_next = match next {
+        Some(idx) if tokens[idx].0 == TokenType::Terminate => after(&tokens[idx].1),
+        Some(idx) => pos(idx),
+        None => (usize::MAX, 0),
+    };
+    let ends = match next {
+        Some(idx) if pos(idx).0 == at.0 => vec![through_next],
+        _ => vec![(at.0, usize::MAX), through_next],
+    };
+
+    for end in ends {
+        let analysis = super::analyse(file, &blank(text, start, end));
+        if let Some(aprogram) = &analysis.aprogram {
+            let func = find_func(aprogram, analysis.source, tokens, open)?;
+            return Some((func, commas));
+        }
+    }
+
+    return None;
+}
+
+// Position just past a token
+fn after(loc: &FileLocation) -> Pos {
+    match loc {
+        FileLocation::Loc {
+            end_line, end_col, ..
+        } => return (*end_line, *end_col + 1),
+        _ => panic!(),
+    }
+}
+
+fn help(func: &AFunc, active: usize) -> String {
+    let (label, params) = label(func);
+    let params: Vec<String> = params
+        .iter()
+        .map(|(start, end)| format!("{{\"label\":[{start},{end}]}}"))
+        .collect();
+
+    return format!(
+        "{{\"signatures\":[{{\"label\":{},\"parameters\":[{}]}}],\"activeSignature\":0,\"activeParameter\":{active}}}",
+        json_string(&label),
+        params.join(",")
+    );
+}
+
+// `textDocument/signatureHelp`. The innermost call around the position is
+// shown, with the argument the position is in as the active parameter.
+pub fn signature_help(file: &Path, text: &str, position: Position) -> String {
+    let analysis = super::analyse(file, text);
+    let doc = Document::new(text);
+    let at: Pos = (position.line, doc.lexer_col(position));
+
+    // A call that is still being typed stops the document from parsing
+    let aprogram = match &analysis.aprogram {
+        Some(aprogram) => aprogram,
+        None => {
+            let result = match typed_call(file, text, &analysis.tokens, at) {
+                Some((func, active)) => help(&func, active),
+                None => String::from("null"),
+            };
+            return super::response(Some(result), &analysis.errors);
+        }
+    };
+
+    let pos = |idx: usize| -> Pos { analysis.tokens[idx].1.start() };
+    let call = calls(aprogram, analysis.source, &analysis.tokens)
+        .into_iter()
+        .filter(|call| pos(call.open) < at && at <= pos(call.close))
+        .max_by_key(|call| call.open);
+
+    let result = match call {
+        Some(call) => {
+            let active = call.commas.iter().filter(|comma| pos(**comma) < at).count();
+            help(&call.func, active)
+        }
+        None => String::from("null"),
+    };
+
+    return super::response(Some(result), &analysis.errors);
+}
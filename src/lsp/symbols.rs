@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use crate::{
+    active_parser::names as nm,
+    errors::{json_string, FileLocation, SourceId},
+    lexer::tokens::{KeyWord, Operator, Token, TokenType},
+    lsp::{Document, Range},
+    parser::{Function, ObjectType, Program, Type, VarSigniture},
+};
+
+// Kinds of symbol from the LSP specification
+const METHOD: usize = 6;
+const CONSTRUCTOR: usize = 9;
+const FIELD: usize = 8;
+const FUNCTION: usize = 12;
+const STRUCT: usize = 23;
+
+struct Symbol {
+    name: String,
+    detail: String,
+    kind: usize,
+    // The whole declaration
+    range: Range,
+    // The name of the declaration
+    selection: Range,
+    children: Vec<Symbol>,
+}
+impl Symbol {
+    fn json(&self) -> String {
+        let children: Vec<String> = self.children.iter().map(|child| child.json()).collect();
+
+        return format!(
+            "{{\"name\":{},\"detail\":{},\"kind\":{},\"range\":{},\"selectionRange\":{},\"children\":[{}]}}",
+            json_string(&self.name),
+            json_string(&self.detail),
+            self.kind,
+            self.range.json(),
+            self.selection.json(),
+            children.join(",")
+        );
+    }
+}
+
+struct Outline<'a> {
+    source: SourceId,
+    doc: Document<'a>,
+    tokens: &'a [Token],
+}
+impl<'a> Outline<'a> {
+    fn in_source(&self, loc: &FileLocation) -> bool {
+        match loc {
+            FileLocation::Loc { file, .. } => return *file == self.source,
+            _ => return false,
+        }
+    }
+
+    // Range of a declaration from its keyword to the end of its block, or to
+    // the end of the term for a field
+    fn extent(&self, name: &FileLocation) -> Option<Range> {
+        let idx = self.tokens.iter().position(|token| token.1 == *name)?;
+
+        let keyword = self.tokens[..idx].iter().rposition(|token| {
+            matches!(
+                token.0,
+                TokenType::KeyWord(KeyWord::Struct | KeyWord::Func | KeyWord::Var)
+            )
+        })?;
+
+        let end = match self.tokens[keyword].0 {
+            TokenType::KeyWord(KeyWord::Var) => self.tokens[idx..]
+                .iter()
+                .position(|token| token.0 == TokenType::Terminate)?,
+            _ => {
+                let mut depth = 0;
+                self.tokens[idx..].iter().position(|token| {
+                    match token.0 {
+                        TokenType::Operator(Operator::OpenBlock) => depth += 1,
+                        TokenType::Operator(Operator::CloseBlock) => depth -= 1,
+                        _ => return false,
+                    }
+                    return depth == 0;
+                })?
+            }
+        };
+
+        let start = self.doc.name_range(&self.tokens[keyword].1, "")?.start;
+        // Blocks and terms end with a single character token
+        let end = self.doc.name_range(&self.tokens[idx + end].1, "}")?.end;
+        return Some(Range { start, end });
+    }
+
+    fn symbol(
+        &self,
+        name: &str,
+        loc: &FileLocation,
+        detail: String,
+        kind: usize,
+        children: Vec<Symbol>,
+    ) -> Option<Symbol> {
+        let selection = self.doc.name_range(loc, name)?;
+        let range = match self.extent(loc) {
+            Some(range) => range,
+            None => selection,
+        };
+
+        return Some(Symbol {
+            name: name.to_string(),
+            detail,
+            kind,
+            range,
+            selection,
+            children,
+        });
+    }
+
+    fn function(&self, function: &Function, kind: usize) -> Option<Symbol> {
+        let kind = match function.name == nm::F_NEW && kind == METHOD {
+            true => CONSTRUCTOR,
+            false => kind,
+        };
+
+        return self.symbol(
+            &function.name,
+            &function.loc,
+            signature(function),
+            kind,
+            Vec::new(),
+        );
+    }
+
+    fn field(&self, field: &VarSigniture) -> Option<Symbol> {
+        return self.symbol(
+            &field.identity,
+            &field.loc,
+            type_name(&field.argtype),
+            FIELD,
+            Vec::new(),
+        );
+    }
+}
+
+fn type_name(_type: &Type) -> String {
+    match _type {
+        Type::Array { _type, .. } => return format!("{}[]", type_name(_type)),
+        Type::Object { object } => match &object.kind {
+            ObjectType::Identity(id) => return id.clone(),
+            _ => panic!(),
+        },
+    }
+}
+
+// A function's return type and arguments, as they are written after `func`
+fn signature(function: &Function) -> String {
+    let args: Vec<String> = function
+        .args
+        .iter()
+        .map(|arg| format!("{} {}", type_name(&arg.argtype), arg.identity))
+        .collect();
+
+    match args.is_empty() {
+        true => return type_name(&function.returntype),
+        false => return format!("{}: {}", type_name(&function.returntype), args.join(", ")),
+    }
+}
+
+// Structs with their fields and methods, and free functions, in the order
+// they are declared
+fn outline(program: &Program, source: SourceId, text: &str, tokens: &[Token]) -> Vec<Symbol> {
+    let outline = Outline {
+        source,
+        doc: Document::new(text),
+        tokens,
+    };
+
+    let mut symbols = Vec::new();
+    for st in &program.structs {
+        if !outline.in_source(&st.loc) {
+            continue;
+        }
+
+        let mut children: Vec<Symbol> = st
+            .properties
+            .iter()
+            .filter_map(|field| outline.field(field))
+            .collect();
+        children.extend(
+            st.methods
+                .iter()
+                .filter_map(|method| outline.function(method, METHOD)),
+        );
+        children.sort_by(|a, b| a.selection.start.partial_cmp(&b.selection.start).unwrap());
+
+        if let Some(symbol) = outline.symbol(&st.name, &st.loc, String::new(), STRUCT, children) {
+            symbols.push(symbol);
+        }
+    }
+
+    for function in &program.functions {
+        if outline.in_source(&function.loc) {
+            symbols.extend(outline.function(function, FUNCTION));
+        }
+    }
+
+    symbols.sort_by(|a, b| a.selection.start.partial_cmp(&b.selection.start).unwrap());
+    return symbols;
+}
+
+// `textDocument/documentSymbol`
+pub fn document_symbols(file: &Path, text: &str) -> String {
+    let analysis = super::analyse(file, text);
+
+    let result = match &analysis.program {
+        Some(program) => {
+            let symbols: Vec<String> = outline(program, analysis.source, text, &analysis.tokens)
+                .iter()
+                .map(|symbol| symbol.json())
+                .collect();
+            Some(format!("[{}]", symbols.join(",")))
+        }
+        None => None,
+    };
+
+    return super::response(result, &analysis.errors);
+}
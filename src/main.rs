@@ -1,130 +1,199 @@
 mod active_parser;
+mod check;
 mod cli;
 mod errors;
 mod finterpretor;
 mod flat_ir;
 mod formmatter;
 mod lexer;
+mod lint;
 mod lsp;
 mod parser;
+mod repl;
 
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::process::exit;
 
 use clap::Parser;
-use errors::{ErrorType, FileLocation, ManagerError};
+use errors::{codes, source_map, ErrorType, FileLocation, ManagerError};
 use lsp::{lsp, Lsp};
 
 fn main() {
     let args = cli::Args::parse();
 
+    errors::set_json_messages(args.message_format == cli::MessageFormat::Json);
+    colored::control::set_override(match args.color {
+        cli::ColorChoice::Always => true,
+        cli::ColorChoice::Never => false,
+        cli::ColorChoice::Auto => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+            !no_color && std::io::stdout().is_terminal()
+        }
+    });
+
     match &args.cmd {
-        cli::Command::Debug { file, .. } | cli::Command::Run { file, .. } => {
+        cli::Command::Debug { file, .. }
+        | cli::Command::Run { file, .. }
+        | cli::Command::Dump { file, .. } => {
+            let stage = match &args.cmd {
+                cli::Command::Dump { stage, .. } => Some(*stage),
+                _ => None,
+            };
+
             let program = {
                 let mut program = match std::fs::read_to_string(&file) {
                     Ok(program) => program,
                     Err(err) => {
                         eprintln!(
                             "{}",
-                            ManagerError(
+                            ManagerError::new(
                                 format!("Could not open program file | {err}"),
-                                FileLocation::None
+                                FileLocation::None,
+                                codes::M0001
                             )
-                            .prettify()
+                            .report()
                         );
-                        return;
+                        exit(errors::EXIT_MANAGER);
                     }
                 };
                 program.push(' ');
                 program
             };
+            let file = source_map::add_source(file.clone(), program.clone());
 
             let lex_out = match lexer::lex(&program, false, &file, "", &[]) {
                 Ok(lex) => lex,
                 Err(err) => {
-                    println!("{}", err.prettify());
-                    return;
+                    println!("{}", err.report());
+                    exit(errors::EXIT_LEXER);
                 }
             };
-            let parse_out = match parser::parse(lex_out, &file) {
-                Ok(parse) => parse,
-                Err(err) => {
-                    match err {
-                        ErrorType::Parser(err) => println!("{}", err.prettify()),
-                        ErrorType::Lexer(err) => println!("{}", err.prettify()),
-                        _ => panic!(),
+
+            if stage == Some(cli::Stage::Tokens) {
+                for token in &lex_out {
+                    let loc = match &token.1 {
+                        FileLocation::Loc {
+                            start_line,
+                            start_col,
+                            ..
+                        } => format!("{}:{}", start_line + 1, start_col),
+                        _ => String::new(),
                     };
-                    return;
+                    println!("{loc: <10}{:?}", token.0);
                 }
+                return;
+            }
+
+            let parse_out = match parser::parse(lex_out, &file) {
+                Ok(parse) => parse,
+                Err(err) => match err {
+                    ErrorType::Parser(err) => {
+                        println!("{}", err.report());
+                        exit(errors::EXIT_PARSER);
+                    }
+                    ErrorType::Lexer(err) => {
+                        println!("{}", err.report());
+                        exit(errors::EXIT_LEXER);
+                    }
+                    _ => panic!(),
+                },
             };
 
+            if stage == Some(cli::Stage::Ast) {
+                println!("{:#?}", parse_out);
+                return;
+            }
+
             let aparse_out = match active_parser::aparse(&parse_out) {
                 Ok(aparse) => aparse,
                 Err(err) => {
-                    println!("{}", err.prettify());
-                    return;
+                    println!("{}", err.report());
+                    exit(errors::EXIT_APARSER);
                 }
             };
 
+            match stage {
+                Some(cli::Stage::Aast) => {
+                    println!("{:#?}", aparse_out);
+                    return;
+                }
+                Some(cli::Stage::Ir) => {
+                    print!(
+                        "{}",
+                        flat_ir::disassemble(&flat_ir::flatten(&aparse_out, false))
+                    );
+                    return;
+                }
+                _ => {}
+            }
+
             let interpretor_out = match &args.cmd {
-                cli::Command::Run { args, .. } => {
+                cli::Command::Run { args, sandbox, .. } => {
                     let flat_ir_out = flat_ir::flatten(&aparse_out, false);
-                    finterpretor::interpret(&flat_ir_out, args, false)
+                    finterpretor::interpret(&flat_ir_out, args, false, sandbox.to_sandbox())
                 }
-                cli::Command::Debug { args, .. } => {
+                cli::Command::Debug { args, sandbox, .. } => {
                     let flat_ir_out = flat_ir::flatten(&aparse_out, true);
-                    finterpretor::interpret(&flat_ir_out, args, true)
+                    finterpretor::interpret(&flat_ir_out, args, true, sandbox.to_sandbox())
                 }
                 _ => panic!(),
             };
 
-            if let Err(err) = interpretor_out {
-                println!("{}", err.prettify());
+            match interpretor_out {
+                Ok(status) => exit(status),
+                Err(err) => {
+                    println!("{}", err.report());
+                    exit(errors::EXIT_RUNTIME);
+                }
             }
         }
-        cli::Command::Format { file, to_stdout } => {
-            let program = {
-                let mut program = match std::fs::read_to_string(&file) {
-                    Ok(program) => program,
-                    Err(err) => {
-                        eprintln!(
-                            "{}",
-                            ManagerError(
-                                format!("Could not open program file. | {err}"),
-                                FileLocation::None
-                            )
-                            .prettify()
-                        );
-                        return;
-                    }
+        cli::Command::Check { paths } => exit(check::check(paths)),
+        cli::Command::Lint { paths } => exit(check::lint(paths)),
+        cli::Command::Explain { code: None } => {
+            for explanation in &codes::EXPLANATIONS {
+                println!("{}  {}", explanation.code, explanation.title);
+            }
+        }
+        cli::Command::Explain { code: Some(code) } => match codes::explain(code) {
+            Some(explanation) => {
+                let indent = |text: &str| {
+                    text.lines()
+                        .map(|line| format!("    {line}"))
+                        .collect::<Vec<String>>()
+                        .join("\n")
                 };
-                program.push(' ');
-                program
-            };
-
-            let text = formmatter::format(&program, 4);
-
-            let to_stdout = match to_stdout {
-                Some(some) => *some,
-                None => false,
-            };
-
-            match to_stdout {
-                true => println!("{}", text),
-                false => {
-                    if let Err(err) = std::fs::write(&file, text) {
-                        eprintln!(
-                            "{}",
-                            ManagerError(
-                                format!("Could not write to program file. | {err}"),
-                                FileLocation::None,
-                            )
-                            .prettify()
-                        );
 
-                        return;
-                    }
-                }
+                println!("{}: {}\n", explanation.code, explanation.title);
+                println!("{}\n", explanation.description);
+                println!("Erroneous example:\n\n{}\n", indent(explanation.wrong));
+                println!("Corrected:\n\n{}", indent(explanation.right));
+            }
+            None => {
+                eprintln!(
+                    "{}",
+                    ManagerError::new(
+                        format!("{code} is not an error code"),
+                        FileLocation::None,
+                        codes::M0005
+                    )
+                    .report()
+                );
+                exit(errors::EXIT_MANAGER);
             }
+        },
+        cli::Command::Repl { sandbox } => repl::repl(sandbox.to_sandbox()),
+        cli::Command::Format {
+            paths,
+            check,
+            stdout,
+        } => {
+            let mode = match (check, stdout) {
+                (true, _) => formmatter::files::Mode::Check,
+                (_, true) => formmatter::files::Mode::Stdout,
+                _ => formmatter::files::Mode::Write,
+            };
+            exit(formmatter::files::format_paths(paths, mode));
         }
         cli::Command::Update => {
             println!("Starting update.");
@@ -136,16 +205,17 @@ fn main() {
             match result {
                 Ok(_) => println!("Update complete via cargo."),
                 Err(err) => {
-                    let error = ManagerError(
+                    let error = ManagerError::new(
                         format!(
                             "Attempt to update TermsLang spawned an error.\n{}\n\n{}",
                             "Ensure you have cargo installed on your PATH.", err
                         ),
                         FileLocation::None,
+                        codes::M0003,
                     );
 
-                    eprintln!("{}", error.prettify());
-                    return;
+                    eprintln!("{}", error.report());
+                    exit(errors::EXIT_MANAGER);
                 }
             }
         }
@@ -161,18 +231,20 @@ fn main() {
                     Err(err) => {
                         eprintln!(
                             "{}",
-                            ManagerError(
+                            ManagerError::new(
                                 format!("Could not open program file | {err}"),
-                                FileLocation::None
+                                FileLocation::None,
+                                codes::M0001
                             )
-                            .prettify()
+                            .report()
                         );
-                        return;
+                        exit(errors::EXIT_MANAGER);
                     }
                 };
                 program.push(' ');
                 program
             };
+            let file = source_map::add_source(file.clone(), program.clone());
 
             let lex_out = match lexer::lex(&program, false, &file, "", &[]) {
                 Ok(lex) => lex,
@@ -180,6 +252,7 @@ fn main() {
                     let lsp = Lsp {
                         vars: Vec::new(),
                         errors: vec![ErrorType::Lexer(err)],
+                        warnings: Vec::new(),
                         structs: HashMap::new(),
                         functions: HashMap::new(),
                     };
@@ -194,8 +267,60 @@ fn main() {
                 None => false,
             };
 
-            let lsp = lsp(lex_out, file, *line, *col, run_parse);
+            let lsp = lsp(lex_out, &file, *line, *col, run_parse);
             println!("{}", lsp.json())
         }
+        cli::Command::Rename { target, new_name } => {
+            exit(lsp::rename::rename_files(target, new_name));
+        }
+        cli::Command::LspRequest { request } => {
+            let doc = match request {
+                cli::LspRequest::Formatting { doc }
+                | cli::LspRequest::RangeFormatting { doc, .. }
+                | cli::LspRequest::SemanticTokens { doc }
+                | cli::LspRequest::DocumentSymbol { doc }
+                | cli::LspRequest::Rename { doc, .. }
+                | cli::LspRequest::SignatureHelp { doc, .. }
+                | cli::LspRequest::InlayHint { doc, .. } => doc,
+                cli::LspRequest::SemanticTokensLegend => {
+                    println!("{}", lsp::response(Some(lsp::semantic::legend()), &[]));
+                    return;
+                }
+            };
+
+            let text = match lsp::read_document(doc) {
+                Ok(text) => text,
+                Err(err) => {
+                    println!("{}", lsp::response(None, &[ErrorType::Manager(err)]));
+                    return;
+                }
+            };
+
+            let response = match request {
+                cli::LspRequest::Formatting { .. } => {
+                    lsp::format::formatting(&doc.file, text, None)
+                }
+                cli::LspRequest::RangeFormatting { range, .. } => {
+                    lsp::format::formatting(&doc.file, text, Some(*range))
+                }
+                cli::LspRequest::SemanticTokens { .. } => {
+                    lsp::semantic::semantic_tokens(&doc.file, &text)
+                }
+                cli::LspRequest::DocumentSymbol { .. } => {
+                    lsp::symbols::document_symbols(&doc.file, &text)
+                }
+                cli::LspRequest::Rename {
+                    position, new_name, ..
+                } => lsp::rename::rename_request(&doc.file, &text, *position, new_name),
+                cli::LspRequest::SignatureHelp { position, .. } => {
+                    lsp::signature::signature_help(&doc.file, &text, *position)
+                }
+                cli::LspRequest::InlayHint { range, .. } => {
+                    lsp::inlay::inlay_hints(&doc.file, &text, *range)
+                }
+                cli::LspRequest::SemanticTokensLegend => panic!(),
+            };
+            println!("{response}");
+        }
     }
 }
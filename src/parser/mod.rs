@@ -1,15 +1,15 @@
 use std::{fs, path::PathBuf};
 
 use crate::{
-    errors::{ErrorType, FileLocation, ParserError},
+    errors::{codes, source_map, suggest, ErrorType, FileLocation, ParserError, SourceId},
     lexer::{
-        self,
+        self, syntax,
         tokens::{KeyWord, Operator, Token, TokenType},
     },
 };
 
 use self::{
-    parse_object::parse_object_peekable,
+    parse_object::parse_object_assignable,
     parse_operand_block::{parse_operand_block, OperandExpression},
     parse_type::{parse_type, parse_var_sig},
 };
@@ -91,12 +91,23 @@ pub struct Call {
 pub struct VarSigniture {
     pub identity: String,
     pub argtype: Type,
+    // Location of the name
+    pub loc: FileLocation,
 }
 
 #[derive(Debug, Clone)]
 pub struct Program {
     pub structs: Vec<Struct>,
     pub functions: Vec<Function>,
+    pub imports: Vec<Import>,
+}
+
+// Objects a file imports and the path they come from, kept so tools can tell
+// which imports go unused and print them back
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub objects: Vec<(String, FileLocation)>,
+    pub path: String,
 }
 
 #[derive(Debug, Clone)]
@@ -121,6 +132,13 @@ pub struct TermBlock {
     pub terms: Vec<Term>,
 }
 
+#[derive(Debug, Clone)]
+pub enum ReplEntry {
+    Declarations(Program),
+    Terms(TermBlock),
+    Expression(OperandExpression),
+}
+
 #[derive(Debug, Clone)]
 pub enum Term {
     Print {
@@ -131,6 +149,7 @@ pub enum Term {
         name: String,
         vartype: Type,
         value: OperandExpression,
+        loc: FileLocation,
     },
     Return {
         value: OperandExpression,
@@ -149,6 +168,8 @@ pub enum Term {
         counter: String,
         conditional: OperandExpression,
         block: TermBlock,
+        // Location of the counter
+        loc: FileLocation,
     },
     Break(FileLocation),
     Continue(FileLocation),
@@ -161,7 +182,7 @@ pub enum Term {
 fn parse_term(
     lead_token: Token,
     token_stream: &mut TokenStream,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<Term, ParserError> {
     // Parse print
     if let Token(TokenType::KeyWord(KeyWord::Print), _) = lead_token {
@@ -182,20 +203,22 @@ fn parse_term(
     // Parse var declaration
     if let Token(TokenType::KeyWord(KeyWord::Var), _) = lead_token {
         let vartype = parse_type(token_stream, file)?;
-        let name = match token_stream.advance().cloned() {
+        let (name, loc) = match token_stream.advance().cloned() {
             Some(op) => match op.0 {
-                TokenType::Identity(id) => id,
+                TokenType::Identity(id) => (id, op.1),
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token in place of variable name".to_string(),
                         op.1,
+                        codes::P0006,
                     ))
                 }
             },
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected type variable name".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0006,
                 ))
             }
         };
@@ -203,15 +226,17 @@ fn parse_term(
         match token_stream.advance() {
             Some(Token(TokenType::Operator(Operator::Set), _)) => {}
             Some(Token(_, pos)) => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Invalid token expected set operator".to_string(),
                     pos.clone(),
+                    codes::P0011,
                 ))
             }
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected set operator".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0011,
                 ))
             }
         }
@@ -222,6 +247,7 @@ fn parse_term(
             name: name.to_owned(),
             vartype,
             value,
+            loc,
         });
     }
 
@@ -234,7 +260,7 @@ fn parse_term(
 
     // Parse var update
     if let Token(TokenType::KeyWord(KeyWord::UpdateVar), _) = lead_token {
-        let var = parse_object_peekable(token_stream, file)?;
+        let var = parse_object_assignable(token_stream, file)?;
 
         let set_operator = match token_stream.advance() {
             Some(Token(TokenType::Operator(operator), pos)) => match operator {
@@ -246,22 +272,25 @@ fn parse_term(
                 Operator::SetModulo => operator,
                 Operator::SetExponent => operator,
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected operator in update operation".to_string(),
                         pos.clone(),
+                        codes::P0011,
                     ))
                 }
             },
             Some(Token(_, pos)) => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in update operation".to_string(),
                     pos.clone(),
+                    codes::P0011,
                 ))
             }
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected set operator or set operator variant".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0011,
                 ))
             }
         }
@@ -297,16 +326,18 @@ fn parse_term(
                         parse_block(token_stream, file)?
                     }
                     _ => {
-                        return Err(ParserError(
+                        return Err(ParserError::new(
                             "Unexpected token in else declaration".to_string(),
                             token.1.clone(),
+                            codes::P0004,
                         ))
                     }
                 },
                 None => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Expected else body".to_string(),
-                        FileLocation::End { file: file.clone() },
+                        FileLocation::End { file: *file },
+                        codes::P0007,
                     ))
                 }
             },
@@ -327,20 +358,22 @@ fn parse_term(
 
     // Parse loop
     if let Token(TokenType::KeyWord(KeyWord::Loop), _) = lead_token {
-        let counter = match token_stream.advance().cloned() {
+        let (counter, loc) = match token_stream.advance().cloned() {
             Some(op) => match op.0 {
-                TokenType::Identity(id) => id,
+                TokenType::Identity(id) => (id, op.1),
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token in place of loop counter name".to_string(),
                         op.1,
+                        codes::P0006,
                     ))
                 }
             },
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected loop counter name".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0006,
                 ))
             }
         };
@@ -349,16 +382,18 @@ fn parse_term(
         match token_stream.advance() {
             Some(Token(TokenType::Operator(Operator::Colon), _)) => {}
             Some(token) => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in loop signiture".to_string(),
                     token.1.clone(),
+                    codes::P0004,
                 ))
             }
 
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Premeture end to loop definition".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0005,
                 ))
             }
         };
@@ -376,6 +411,7 @@ fn parse_term(
             counter,
             conditional,
             block,
+            loc,
         });
     }
 
@@ -385,16 +421,18 @@ fn parse_term(
             Some(token) => match token.0 {
                 TokenType::Terminate => Ok(Term::Break(token.1.clone())),
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token at after break".to_string(),
                         token.1.clone(),
+                        codes::P0004,
                     ))
                 }
             },
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected line terminator".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0003,
                 ))
             }
         };
@@ -406,16 +444,18 @@ fn parse_term(
             Some(token) => match token.0 {
                 TokenType::Terminate => Ok(Term::Continue(token.1.clone())),
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token at after continue".to_string(),
                         token.1.clone(),
+                        codes::P0004,
                     ))
                 }
             },
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected line terminator".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0003,
                 ))
             }
         };
@@ -428,14 +468,35 @@ fn parse_term(
         });
     }
 
-    return Err(ParserError(
-        "Unrecognized term".to_string(),
+    return Err(ParserError::new(
+        format!(
+            "Unrecognized term.{}",
+            keyword_suggestion(&lead_token, &syntax::TERM_KEYWORDS)
+        ),
         lead_token.1.clone(),
+        codes::P0012,
     ));
 }
 
+// Suggest a keyword for a name that was probably meant to be one
+fn keyword_suggestion(token: &Token, keywords: &[&str]) -> String {
+    let id = match &token.0 {
+        TokenType::Identity(id) => id,
+        _ => return String::new(),
+    };
+
+    let base = id.rsplit("::").next().unwrap();
+    for (alias, keyword) in syntax::KEYWORD_ALIASES {
+        if alias == base && keywords.contains(&keyword) {
+            return format!(" Did you mean `{keyword}`?");
+        }
+    }
+
+    return suggest::did_you_mean(base, keywords.iter().copied());
+}
+
 // Parse function
-fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function, ParserError> {
+fn parse_func(token_stream: &mut TokenStream, file: &SourceId) -> Result<Function, ParserError> {
     // Get return type of function
     let returntype = parse_type::parse_type(token_stream, file)?;
 
@@ -444,16 +505,18 @@ fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function
         Some(op) => match op.0 {
             TokenType::Identity(id) => (id, op.1),
             _ => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token instead of function name".to_string(),
                     op.1,
+                    codes::P0006,
                 ))
             }
         },
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected function name".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0006,
             ))
         }
     };
@@ -463,15 +526,17 @@ fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function
         Some(Token(TokenType::Operator(Operator::Colon), _)) => true,
         Some(Token(TokenType::Operator(Operator::OpenBlock), _)) => false,
         Some(token) => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Unexpected token in function signiture".to_string(),
                 token.1.clone(),
+                codes::P0004,
             ))
         }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Premature end to function signiture".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0005,
             ))
         }
     };
@@ -488,9 +553,10 @@ fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function
                 let token = match token_stream.advance() {
                     Some(token) => token,
                     None => {
-                        return Err(ParserError(
+                        return Err(ParserError::new(
                             "Expected function body".to_string(),
-                            FileLocation::End { file: file.clone() },
+                            FileLocation::End { file: *file },
+                            codes::P0007,
                         ))
                     }
                 };
@@ -513,15 +579,17 @@ fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function
                     Some(Token(TokenType::Operator(Operator::OpenBlock), _)) => break,
                     Some(Token(TokenType::Operator(Operator::Comma), _)) => continue,
                     Some(token) => {
-                        return Err(ParserError(
+                        return Err(ParserError::new(
                             "Unexpected token in function signiture".to_string(),
                             token.1.clone(),
+                            codes::P0004,
                         ))
                     }
                     None => {
-                        return Err(ParserError(
+                        return Err(ParserError::new(
                             "Expected function body".to_string(),
-                            FileLocation::End { file: file.clone() },
+                            FileLocation::End { file: *file },
+                            codes::P0007,
                         ))
                     }
                 }
@@ -550,21 +618,23 @@ fn parse_func(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Function
 }
 
 // Parse struct
-fn parse_struct(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Struct, ParserError> {
+fn parse_struct(token_stream: &mut TokenStream, file: &SourceId) -> Result<Struct, ParserError> {
     let (name, loc) = match token_stream.advance().cloned() {
         Some(op) => match op.0 {
             TokenType::Identity(id) => (id, op.1),
             _ => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in place of class name".to_string(),
                     (op.1).clone(),
+                    codes::P0006,
                 ))
             }
         },
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected class name".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0006,
             ))
         }
     };
@@ -573,15 +643,17 @@ fn parse_struct(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Struct
     match token_stream.advance() {
         Some(Token(TokenType::Operator(Operator::OpenBlock), _)) => {}
         Some(token) => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Unexpected token in class definition".to_string(),
                 token.1.clone(),
+                codes::P0004,
             ))
         }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Premature end to class definition".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0005,
             ))
         }
     };
@@ -604,15 +676,17 @@ fn parse_struct(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Struct
                     match token_stream.advance() {
                         Some(Token(TokenType::Terminate, _)) => {}
                         Some(token) => {
-                            return Err(ParserError(
+                            return Err(ParserError::new(
                                 "Unexpected token in property definition. You can only initialize a delcaration statement inside a function.".to_string(),
                                 token.1.clone(),
+                                codes::P0015,
                             ))
                         }
                         None => {
-                            return Err(ParserError(
+                            return Err(ParserError::new(
                                 "Expected line terminator".to_string(),
-                                FileLocation::End { file: file.clone() },
+                                FileLocation::End { file: *file },
+                                codes::P0003,
                             ))
                         }
                     };
@@ -624,16 +698,18 @@ fn parse_struct(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Struct
                     methods.push(func);
                 }
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token within class block".to_string(),
                         token.1.clone(),
+                        codes::P0004,
                     ))
                 }
             },
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected class block close".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0007,
                 ))
             }
         }
@@ -648,17 +724,24 @@ fn parse_struct(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Struct
 }
 
 // Parse code within block
-fn parse_block(token_stream: &mut TokenStream, file: &PathBuf) -> Result<TermBlock, ParserError> {
+fn parse_block(token_stream: &mut TokenStream, file: &SourceId) -> Result<TermBlock, ParserError> {
     // Check for block open
     let mut block = match token_stream.advance() {
         Some(Token(TokenType::Operator(Operator::OpenBlock), _)) => TermBlock {
             terms: Vec::<Term>::new(),
         },
-        Some(token) => return Err(ParserError("Expected block".to_string(), token.1.clone())),
+        Some(token) => {
+            return Err(ParserError::new(
+                "Expected block".to_string(),
+                token.1.clone(),
+                codes::P0007,
+            ))
+        }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected block".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0007,
             ))
         }
     };
@@ -680,24 +763,27 @@ fn parse_block(token_stream: &mut TokenStream, file: &PathBuf) -> Result<TermBlo
     match token_stream.current() {
         Some(Token(TokenType::Operator(Operator::CloseBlock), _pos)) => return Ok(block),
         Some(Token(_, pos)) => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Unexpected token at block closing".to_string(),
                 pos.clone(),
+                codes::P0007,
             ))
         }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected block close".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0007,
             ))
         }
     }
 }
 
-fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Program, ErrorType> {
+fn parse_program(token_stream: &mut TokenStream, file: &SourceId) -> Result<Program, ErrorType> {
     let mut program = Program {
         structs: Vec::new(),
         functions: Vec::new(),
+        imports: Vec::new(),
     };
 
     while let Some(token) = token_stream.advance().cloned() {
@@ -717,34 +803,39 @@ fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Progr
                     }),
                 KeyWord::Import => {
                     let mut objects = Vec::new();
+                    let mut object_locs = Vec::new();
                     while let Some(token) = token_stream.advance() {
                         match &token.0 {
                             TokenType::Identity(id) => {
                                 objects.push(id.clone());
+                                object_locs.push((id.clone(), token.1.clone()));
 
                                 match token_stream.advance() {
                                     Some(token) => match token.0 {
                                         TokenType::Operator(Operator::Comma) => continue,
                                         TokenType::KeyWord(KeyWord::Of) => break,
                                         _ => {
-                                            return Err(ErrorType::Parser(ParserError(
+                                            return Err(ErrorType::Parser(ParserError::new(
                                                 "Unexpected token in import.".to_owned(),
                                                 token.1.clone(),
+                                                codes::P0010,
                                             )))
                                         }
                                     },
                                     None => {
-                                        return Err(ErrorType::Parser(ParserError(
+                                        return Err(ErrorType::Parser(ParserError::new(
                                             "Expected import file.".to_owned(),
-                                            FileLocation::End { file: file.clone() },
+                                            FileLocation::End { file: *file },
+                                            codes::P0010,
                                         )))
                                     }
                                 }
                             }
                             _ => {
-                                return Err(ErrorType::Parser(ParserError(
+                                return Err(ErrorType::Parser(ParserError::new(
                                     "Expected object name to import.".to_owned(),
                                     token.1.clone(),
+                                    codes::P0010,
                                 )))
                             }
                         }
@@ -753,9 +844,10 @@ fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Progr
                     let file_token = match token_stream.advance() {
                         Some(t) => t,
                         None => {
-                            return Err(ErrorType::Parser(ParserError(
+                            return Err(ErrorType::Parser(ParserError::new(
                                 "Expected string after import.".to_string(),
-                                FileLocation::End { file: file.clone() },
+                                FileLocation::End { file: *file },
+                                codes::P0010,
                             )))
                         }
                     }
@@ -763,26 +855,32 @@ fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Progr
                     let file_string = match file_token {
                         Token(TokenType::String(file), _) => file.to_owned(),
                         _ => {
-                            return Err(ErrorType::Parser(ParserError(
+                            return Err(ErrorType::Parser(ParserError::new(
                                 "Expected string after import.".to_string(),
                                 file_token.1.clone(),
+                                codes::P0010,
                             )))
                         }
                     };
 
-                    let path = file.parent().unwrap().join(PathBuf::from(file_string));
+                    let path = source_map::source_path(*file)
+                        .parent()
+                        .unwrap()
+                        .join(PathBuf::from(&file_string));
                     match token_stream.advance() {
                         Some(Token(TokenType::Terminate, _)) => {}
                         Some(t) => {
-                            return Err(ErrorType::Parser(ParserError(
+                            return Err(ErrorType::Parser(ParserError::new(
                                 "Expected line terminator".to_string(),
                                 t.1.clone(),
+                                codes::P0003,
                             )))
                         }
                         None => {
-                            return Err(ErrorType::Parser(ParserError(
+                            return Err(ErrorType::Parser(ParserError::new(
                                 "Expected line terminator".to_string(),
-                                FileLocation::End { file: file.clone() },
+                                FileLocation::End { file: *file },
+                                codes::P0003,
                             )))
                         }
                     }
@@ -791,41 +889,53 @@ fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Progr
                         let mut module = match fs::read_to_string(&path) {
                             Ok(ok) => ok,
                             Err(_) => {
-                                return Err(ErrorType::Parser(ParserError(
+                                return Err(ErrorType::Parser(ParserError::new(
                                     "Cannot read mod input file".to_string(),
                                     file_token.1.clone(),
+                                    codes::P0010,
                                 )))
                             }
                         };
                         module.push(' ');
                         module
                     };
+                    let module_id = source_map::add_source(path.clone(), module.clone());
                     let lex_out = match lexer::lex(
                         &module,
                         false,
-                        &path,
+                        &module_id,
                         &format!("{}::", path.to_string_lossy()),
                         &objects,
                     ) {
                         Ok(ok) => ok,
                         Err(err) => return Err(ErrorType::Lexer(err)),
                     };
-                    let mut parse_out = parse(lex_out, &path)?;
+                    let mut parse_out = parse(lex_out, &module_id)?;
 
                     program.structs.append(&mut parse_out.structs);
                     program.functions.append(&mut parse_out.functions);
+                    program.imports.push(Import {
+                        objects: object_locs,
+                        path: file_string,
+                    });
                 }
                 _ => {
-                    return Err(ErrorType::Parser(ParserError(
+                    return Err(ErrorType::Parser(ParserError::new(
                         format!("Invalid keyword, {}, in program namespace", keyword),
                         token.1,
+                        codes::P0012,
                     )))
                 }
             },
             _ => {
-                return Err(ErrorType::Parser(ParserError(
-                    format!("Invalid token, {}, in program namespace", token.0),
+                return Err(ErrorType::Parser(ParserError::new(
+                    format!(
+                        "Invalid token, {}, in program namespace.{}",
+                        token.0,
+                        keyword_suggestion(&token, &syntax::TOP_LEVEL_KEYWORDS)
+                    ),
                     token.1,
+                    codes::P0012,
                 )))
             }
         }
@@ -835,7 +945,7 @@ fn parse_program(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Progr
 }
 
 // Parse a Token Vector
-pub fn parse(input: Vec<Token>, file: &PathBuf) -> Result<Program, ErrorType> {
+pub fn parse(input: Vec<Token>, file: &SourceId) -> Result<Program, ErrorType> {
     let mut token_stream = TokenStream::new(input);
     let _program_prelude = match token_stream.current() {
         Some(Token(TokenType::String(string), _)) => string,
@@ -851,24 +961,93 @@ pub fn parse(input: Vec<Token>, file: &PathBuf) -> Result<Program, ErrorType> {
             {
                 end_col = 1;
                 loc = FileLocation::Loc {
-                    file: file.clone(),
+                    file: *file,
                     start_line,
                     end_line,
                     start_col,
                     end_col,
                 }
             }
-            return Err(ErrorType::Parser(ParserError(
+            return Err(ErrorType::Parser(ParserError::new(
                 "No program prelude string found".to_string(),
                 loc,
+                codes::P0002,
             )));
         }
         None => {
-            return Err(ErrorType::Parser(ParserError(
+            return Err(ErrorType::Parser(ParserError::new(
                 "Program file empty".to_string(),
                 FileLocation::None,
+                codes::P0001,
             )))
         }
     };
     return parse_program(&mut token_stream, file);
 }
+
+// Parse a single REPL input, which has no prelude string
+pub fn parse_repl(input: Vec<Token>, file: &SourceId) -> Result<ReplEntry, ErrorType> {
+    let lead_token = match input.first() {
+        Some(token) => token.clone(),
+        None => {
+            return Err(ErrorType::Parser(ParserError::new(
+                "Nothing to run".to_string(),
+                FileLocation::None,
+                codes::P0001,
+            )))
+        }
+    };
+
+    // Stand in for the prelude so every parser can advance onto its first token
+    let mut tokens = vec![Token(TokenType::Terminate, FileLocation::None)];
+    tokens.extend(input);
+    let mut token_stream = TokenStream::new(tokens);
+
+    match lead_token.0 {
+        TokenType::KeyWord(KeyWord::Struct | KeyWord::Func) => {
+            return parse_program(&mut token_stream, file).map(ReplEntry::Declarations)
+        }
+        TokenType::KeyWord(KeyWord::Import) => {
+            return Err(ErrorType::Parser(ParserError::new(
+                "Use :load <file> to load declarations from a file.".to_string(),
+                lead_token.1,
+                codes::P0013,
+            )))
+        }
+        TokenType::KeyWord(KeyWord::Return) => {
+            return Err(ErrorType::Parser(ParserError::new(
+                "Cannot return from the top level.".to_string(),
+                lead_token.1,
+                codes::P0013,
+            )))
+        }
+        TokenType::KeyWord(_) => {
+            let mut terms = Vec::new();
+            while let Some(token) = token_stream.advance().cloned() {
+                terms.push(match parse_term(token, &mut token_stream, file) {
+                    Ok(ok) => ok,
+                    Err(err) => return Err(ErrorType::Parser(err)),
+                });
+            }
+
+            return Ok(ReplEntry::Terms(TermBlock { terms }));
+        }
+        _ => {
+            let expression =
+                match parse_operand_block(&mut token_stream, vec![TokenType::Terminate], file) {
+                    Ok(ok) => ok,
+                    Err(err) => return Err(ErrorType::Parser(err)),
+                };
+
+            if let Some(token) = token_stream.advance() {
+                return Err(ErrorType::Parser(ParserError::new(
+                    "Unexpected token after expression".to_string(),
+                    token.1.clone(),
+                    codes::P0008,
+                )));
+            }
+
+            return Ok(ReplEntry::Expression(expression));
+        }
+    }
+}
@@ -1,7 +1,5 @@
-use std::path::PathBuf;
-
 use crate::{
-    errors::{FileLocation, ParserError},
+    errors::{codes, FileLocation, ParserError, SourceId},
     lexer::tokens::{Operator, Token, TokenType},
 };
 
@@ -12,19 +10,21 @@ use super::{
 };
 
 // Parse function call
-fn parse_call(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Call, ParserError> {
+fn parse_call(token_stream: &mut TokenStream, file: &SourceId) -> Result<Call, ParserError> {
     match token_stream.advance() {
         Some(Token(TokenType::Operator(Operator::OpenParen), _)) => {}
         Some(token) => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Unexpected token in call arguments".to_string(),
                 token.1.clone(),
+                codes::P0014,
             ))
         }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected start of call arguments".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0014,
             ))
         }
     };
@@ -55,10 +55,61 @@ fn parse_call(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Call, Pa
     return Ok(Call { args });
 }
 
+// Parse array literal: [1, 2, 3]
+pub fn parse_array_literal(
+    token_stream: &mut TokenStream,
+    file: &SourceId,
+) -> Result<Vec<OperandExpression>, ParserError> {
+    match token_stream.advance() {
+        Some(Token(TokenType::Operator(Operator::OpenBracket), _)) => {}
+        Some(token) => {
+            return Err(ParserError::new(
+                "Unexpected token in array literal".to_string(),
+                token.1.clone(),
+                codes::P0014,
+            ))
+        }
+        None => {
+            return Err(ParserError::new(
+                "Expected start of array literal".to_string(),
+                FileLocation::End { file: *file },
+                codes::P0014,
+            ))
+        }
+    };
+
+    let mut values = Vec::<OperandExpression>::new();
+    loop {
+        // Add clause to check for empty list: []
+        if let Some(Token(TokenType::Operator(Operator::CloseBracket), _)) = token_stream.advance()
+        {
+            break;
+        }
+
+        token_stream.back();
+
+        values.push(parse_operand_block(
+            token_stream,
+            vec![
+                TokenType::Operator(Operator::CloseBracket),
+                TokenType::Operator(Operator::Comma),
+            ],
+            file,
+        )?);
+
+        if let Some(Token(TokenType::Operator(Operator::CloseBracket), _)) = token_stream.current()
+        {
+            break;
+        }
+    }
+
+    return Ok(values);
+}
+
 // Parse identity object Peekable, Noncallable
 pub fn parse_object_peekable(
     token_stream: &mut TokenStream,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<Object, ParserError> {
     match token_stream.advance().cloned() {
         Some(token) => match token.0 {
@@ -66,15 +117,17 @@ pub fn parse_object_peekable(
                 Some(Token(TokenType::Operator(Operator::Dot), pos)) => {
                     match token_stream.advance() {
                         Some(Token(TokenType::Operator(Operator::OpenBracket), _)) => {
-                            return Err(ParserError(
+                            return Err(ParserError::new(
                                 "Cannot call identity at this location".to_string(),
                                 pos.clone(),
+                                codes::P0009,
                             ))
                         }
                         Some(Token(TokenType::Operator(Operator::OpenParen), _)) => {
-                            return Err(ParserError(
+                            return Err(ParserError::new(
                                 "Cannot index identity at this location".to_string(),
                                 pos.clone(),
+                                codes::P0009,
                             ))
                         }
                         _ => {
@@ -97,16 +150,18 @@ pub fn parse_object_peekable(
                 }
             },
             _ => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in place of identity".to_string(),
                     token.1.clone(),
+                    codes::P0006,
                 ));
             }
         },
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected identity".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0006,
             ))
         }
     }
@@ -115,7 +170,7 @@ pub fn parse_object_peekable(
 // Parse identity object Peekable, Callable
 pub fn parse_object_peekable_callable(
     token_stream: &mut TokenStream,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<Object, ParserError> {
     match token_stream.advance().cloned() {
         Some(token) => match token.0 {
@@ -130,6 +185,17 @@ pub fn parse_object_peekable_callable(
                         )?)),
                     });
                 }
+                Some(Token(TokenType::Operator(Operator::OpenBracket), _)) => {
+                    token_stream.back();
+                    return Ok(Object {
+                        loc: token.1,
+                        kind: ObjectType::Identity(id),
+                        sub: Some(Box::new(parse_object_peekable_callable(
+                            token_stream,
+                            file,
+                        )?)),
+                    });
+                }
                 _ => {
                     token_stream.back();
                     return Ok(Object {
@@ -181,6 +247,17 @@ pub fn parse_object_peekable_callable(
                             )?)),
                         });
                     }
+                    Some(Token(TokenType::Operator(Operator::OpenBracket), _)) => {
+                        token_stream.back();
+                        return Ok(Object {
+                            loc: token.1,
+                            kind: ObjectType::Index(Box::new(index)),
+                            sub: Some(Box::new(parse_object_peekable_callable(
+                                token_stream,
+                                file,
+                            )?)),
+                        });
+                    }
                     _ => {
                         token_stream.back();
                         Ok(Object {
@@ -192,37 +269,129 @@ pub fn parse_object_peekable_callable(
                 }
             }
             _ => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in place of identity".to_string(),
                     token.1.clone(),
+                    codes::P0006,
                 ));
             }
         },
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected identity".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0006,
             ))
         }
     }
 }
 
+// Parse identity object Peekable, Assignable: a.b[0].c
+pub fn parse_object_assignable(
+    token_stream: &mut TokenStream,
+    file: &SourceId,
+) -> Result<Object, ParserError> {
+    match token_stream.advance().cloned() {
+        Some(Token(TokenType::Identity(id), loc)) => {
+            return Ok(Object {
+                loc,
+                kind: ObjectType::Identity(id),
+                sub: parse_object_assignable_sub(token_stream, file)?,
+            })
+        }
+        Some(token) => {
+            return Err(ParserError::new(
+                "Unexpected token in place of identity".to_string(),
+                token.1.clone(),
+                codes::P0006,
+            ))
+        }
+        None => {
+            return Err(ParserError::new(
+                "Expected identity".to_string(),
+                FileLocation::End { file: *file },
+                codes::P0006,
+            ))
+        }
+    }
+}
+
+// Parse field or index following an assignable object
+fn parse_object_assignable_sub(
+    token_stream: &mut TokenStream,
+    file: &SourceId,
+) -> Result<Option<Box<Object>>, ParserError> {
+    let index_loc = match token_stream.advance().cloned() {
+        Some(Token(TokenType::Operator(Operator::Dot), _)) => match token_stream.advance().cloned()
+        {
+            Some(Token(TokenType::Identity(id), loc)) => {
+                return Ok(Some(Box::new(Object {
+                    loc,
+                    kind: ObjectType::Identity(id),
+                    sub: parse_object_assignable_sub(token_stream, file)?,
+                })))
+            }
+            Some(Token(TokenType::Operator(Operator::OpenBracket), loc)) => loc,
+            Some(Token(TokenType::Operator(Operator::OpenParen), loc)) => {
+                return Err(ParserError::new(
+                    "Cannot assign to the result of a call".to_string(),
+                    loc,
+                    codes::P0011,
+                ))
+            }
+            Some(token) => {
+                return Err(ParserError::new(
+                    "Unexpected token in place of identity".to_string(),
+                    token.1,
+                    codes::P0006,
+                ))
+            }
+            None => {
+                return Err(ParserError::new(
+                    "Expected identity".to_string(),
+                    FileLocation::End { file: *file },
+                    codes::P0006,
+                ))
+            }
+        },
+        Some(Token(TokenType::Operator(Operator::OpenBracket), loc)) => loc,
+        _ => {
+            token_stream.back();
+            return Ok(None);
+        }
+    };
+
+    let index = parse_operand_block(
+        token_stream,
+        vec![TokenType::Operator(Operator::CloseBracket)],
+        file,
+    )?;
+
+    return Ok(Some(Box::new(Object {
+        loc: index_loc,
+        kind: ObjectType::Index(Box::new(index)),
+        sub: parse_object_assignable_sub(token_stream, file)?,
+    })));
+}
+
 pub fn parse_object_create(
     token_stream: &mut TokenStream,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<ObjectCreate, ParserError> {
     match token_stream.advance() {
         Some(Token(TokenType::Operator(Operator::New), _)) => {}
         Some(token) => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Unexpected token in object creation".to_string(),
                 token.1.clone(),
+                codes::P0014,
             ))
         }
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected creation operator".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0014,
             ))
         }
     };
@@ -1,12 +1,10 @@
-use std::path::PathBuf;
-
 use crate::{
-    errors::{FileLocation, ParserError},
+    errors::{codes, FileLocation, ParserError, SourceId},
     lexer::tokens::{Operator, Token, TokenType},
 };
 
 use super::{
-    parse_object::{parse_object_create, parse_object_peekable_callable},
+    parse_object::{parse_array_literal, parse_object_create, parse_object_peekable_callable},
     Object, ObjectCreate, TokenStream,
 };
 
@@ -28,6 +26,7 @@ pub enum OperandExpressionValue {
     Literal(Token),
     Object(Object),
     Create(ObjectCreate),
+    Array(Vec<OperandExpression>),
 }
 
 #[derive(Debug, Clone)]
@@ -39,9 +38,10 @@ enum OperandComponent {
     Literal(Token),
     Operand(Token),
     Create(ObjectCreate, FileLocation),
+    Array(Vec<OperandExpression>, FileLocation),
 }
 
-fn get_precedent_map() -> Vec<Vec<Operator>> {
+pub fn get_precedent_map() -> Vec<Vec<Operator>> {
     vec![
         vec![Operator::And, Operator::Or],
         vec![
@@ -52,6 +52,10 @@ fn get_precedent_map() -> Vec<Vec<Operator>> {
             Operator::LessOrEqual,
             Operator::NotEqual,
         ],
+        vec![Operator::BitOr],
+        vec![Operator::BitXor],
+        vec![Operator::BitAnd],
+        vec![Operator::ShiftLeft, Operator::ShiftRight],
         vec![Operator::Add, Operator::Subtract],
         vec![Operator::Multiply, Operator::Divide, Operator::Modulo],
         vec![Operator::Exponent],
@@ -92,7 +96,7 @@ fn remove_extra_paren(slice: &mut Vec<OperandComponent>) {
 fn parse_slice(
     mut slice: Vec<OperandComponent>,
     president_map: &Vec<Vec<Operator>>,
-    file: &PathBuf,
+    file: &SourceId,
     loc: &FileLocation,
 ) -> Result<OperandExpression, ParserError> {
     let mut paren_depth = 0;
@@ -121,10 +125,17 @@ fn parse_slice(
                     loc.clone(),
                 ))
             }
+            OperandComponent::Array(values, loc) => {
+                return Ok(OperandExpression(
+                    OperandExpressionValue::Array(values.clone()),
+                    loc.clone(),
+                ))
+            }
             OperandComponent::Operand(token) => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected operator where value should be found".to_string(),
                     token.1.clone(),
+                    codes::P0008,
                 ))
             }
         }
@@ -149,9 +160,10 @@ fn parse_slice(
 
                 // check for extra closing )
                 if paren_depth <= -1 {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unmatched closing operand block found".to_string(),
                         pos.clone(),
+                        codes::P0008,
                     ));
                 }
 
@@ -171,9 +183,10 @@ fn parse_slice(
                                 let slice_end = if operand_component_idx > 0 {
                                     operand_component_idx - 1
                                 } else {
-                                    return Err(ParserError(
+                                    return Err(ParserError::new(
                                         "Expected parenthies to the left of dot".to_string(),
                                         operand_token.1.clone(),
+                                        codes::P0009,
                                     ));
                                 };
 
@@ -185,10 +198,11 @@ fn parse_slice(
                                         if slice_start > 0 {
                                             slice_start -= 1
                                         } else {
-                                            return Err(ParserError(
+                                            return Err(ParserError::new(
                                                 "Expected parenthies to the left of dot"
                                                     .to_string(),
                                                 operand_token.1.clone(),
+                                                codes::P0009,
                                             ));
                                         };
 
@@ -223,9 +237,10 @@ fn parse_slice(
                                 let left_slice = match slice.get(slice_start..slice_end + 1) {
                                     Some(slice) => slice,
                                     None => {
-                                        return Err(ParserError(
+                                        return Err(ParserError::new(
                                             "Expected value right of uniary operator".to_string(),
                                             operand_token.1.clone(),
+                                            codes::P0008,
                                         ))
                                     }
                                 }
@@ -236,33 +251,37 @@ fn parse_slice(
                                         1 => match &some[0] {
                                             OperandComponent::Object(object) => object,
                                             _ => {
-                                                return Err(ParserError(
+                                                return Err(ParserError::new(
                                                     format!("Dot must be followed by object"),
                                                     operand_token.1.clone(),
+                                                    codes::P0009,
                                                 ))
                                             }
                                         },
                                         _ => {
-                                            return Err(ParserError(
+                                            return Err(ParserError::new(
                                                 format!(
                                                     "Cannot have multiple objects following dot"
                                                 ),
                                                 operand_token.1.clone(),
+                                                codes::P0009,
                                             ));
                                         }
                                     },
                                     None => {
-                                        return Err(ParserError(
+                                        return Err(ParserError::new(
                                             format!("Expected object following dot"),
                                             operand_token.1.clone(),
+                                            codes::P0009,
                                         ))
                                     }
                                 };
 
                                 if slice.len() == 0 {
-                                    return Err(ParserError(
+                                    return Err(ParserError::new(
                                         "Expected value right of uniary operator".to_string(),
                                         operand_token.1.clone(),
+                                        codes::P0008,
                                     ));
                                 }
 
@@ -285,17 +304,19 @@ fn parse_slice(
                                 let slice = match slice.get(operand_component_idx + 1..) {
                                     Some(slice) => slice,
                                     None => {
-                                        return Err(ParserError(
+                                        return Err(ParserError::new(
                                             "Expected value right of uniary operator".to_string(),
                                             operand_token.1.clone(),
+                                            codes::P0008,
                                         ))
                                     }
                                 };
 
                                 if slice.len() == 0 {
-                                    return Err(ParserError(
+                                    return Err(ParserError::new(
                                         "Expected value right of uniary operator".to_string(),
                                         operand_token.1.clone(),
+                                        codes::P0008,
                                     ));
                                 }
 
@@ -316,9 +337,10 @@ fn parse_slice(
                             let slice_l = match slice.get(..operand_component_idx) {
                                 Some(slice_l) => slice_l,
                                 None => {
-                                    return Err(ParserError(
+                                    return Err(ParserError::new(
                                         "Expected value left of binary operator".to_string(),
                                         operand_token.1.clone(),
+                                        codes::P0008,
                                     ))
                                 }
                             };
@@ -326,24 +348,27 @@ fn parse_slice(
                             let slice_r = match slice.get(operand_component_idx + 1..) {
                                 Some(slice_r) => slice_r,
                                 None => {
-                                    return Err(ParserError(
+                                    return Err(ParserError::new(
                                         "Expected value right of binary operator".to_string(),
                                         operand_token.1.clone(),
+                                        codes::P0008,
                                     ))
                                 }
                             };
 
                             if slice_r.len() == 0 {
-                                return Err(ParserError(
+                                return Err(ParserError::new(
                                     "Expected value right of binary operator".to_string(),
                                     operand_token.1.clone(),
+                                    codes::P0008,
                                 ));
                             }
 
                             if slice_l.len() == 0 {
-                                return Err(ParserError(
+                                return Err(ParserError::new(
                                     "Expected value left of binary operator".to_string(),
                                     operand_token.1.clone(),
+                                    codes::P0008,
                                 ));
                             }
 
@@ -372,19 +397,20 @@ fn parse_slice(
         }
     }
 
-    return Err(ParserError(
+    return Err(ParserError::new(
         "Operand parse falls through".to_string(),
         match slice.last() {
             Some(OperandComponent::Operand(token)) => token.1.clone(),
             _ => loc.clone(),
         },
+        codes::P0008,
     ));
 }
 
 pub fn parse_operand_block(
     token_stream: &mut TokenStream,
     terminating_tokens: Vec<TokenType>,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<OperandExpression, ParserError> {
     let mut loc;
     let operand_list = {
@@ -395,9 +421,10 @@ pub fn parse_operand_block(
             let token = match token_stream.advance() {
                 Some(token) => token,
                 None => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Expected end of operand block".to_string(),
-                        FileLocation::End { file: file.clone() },
+                        FileLocation::End { file: *file },
+                        codes::P0008,
                     ))
                 }
             };
@@ -444,9 +471,17 @@ pub fn parse_operand_block(
                     token_stream.back();
                     OperandComponent::Create(parse_object_create(token_stream, file)?, loc)
                 }
+                TokenType::Operator(Operator::OpenBracket) => {
+                    let loc = token.1.clone();
+                    token_stream.back();
+                    OperandComponent::Array(parse_array_literal(token_stream, file)?, loc)
+                }
                 TokenType::Operator(
                     Operator::Add
                     | Operator::And
+                    | Operator::BitAnd
+                    | Operator::BitOr
+                    | Operator::BitXor
                     | Operator::CloseParen
                     | Operator::Divide
                     | Operator::Dot
@@ -462,12 +497,15 @@ pub fn parse_operand_block(
                     | Operator::NotEqual
                     | Operator::OpenParen
                     | Operator::Or
+                    | Operator::ShiftLeft
+                    | Operator::ShiftRight
                     | Operator::Subtract,
                 ) => OperandComponent::Operand(token.clone()),
                 _ => {
-                    return Err(ParserError(
+                    return Err(ParserError::new(
                         "Unexpected token in operand block".to_string(),
                         token.1.clone(),
+                        codes::P0008,
                     ))
                 }
             };
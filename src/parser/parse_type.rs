@@ -1,14 +1,12 @@
-use std::path::PathBuf;
-
 use crate::{
-    errors::{FileLocation, ParserError},
+    errors::{codes, FileLocation, ParserError, SourceId},
     lexer::tokens::{Operator, Token, TokenType},
 };
 
 use super::{parse_object::parse_object_peekable, TokenStream, Type, VarSigniture};
 
 // Parse a type identifier
-pub fn parse_type(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Type, ParserError> {
+pub fn parse_type(token_stream: &mut TokenStream, file: &SourceId) -> Result<Type, ParserError> {
     // Get the typename token
     let typename = parse_object_peekable(token_stream, file)?;
     let location = typename.loc.clone();
@@ -24,15 +22,17 @@ pub fn parse_type(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Type
                 };
             }
             Some(token) => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token instead of closing bracket".to_string(),
                     token.1.clone(),
+                    codes::P0014,
                 ))
             }
             None => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Expected closing bracket".to_string(),
-                    FileLocation::End { file: file.clone() },
+                    FileLocation::End { file: *file },
+                    codes::P0014,
                 ))
             }
         }
@@ -45,26 +45,28 @@ pub fn parse_type(token_stream: &mut TokenStream, file: &PathBuf) -> Result<Type
 // Gen variable signiture: type[] name
 pub fn parse_var_sig(
     token_stream: &mut TokenStream,
-    file: &PathBuf,
+    file: &SourceId,
 ) -> Result<VarSigniture, ParserError> {
     // Get the type of the argument
     let argtype = parse_type(token_stream, file)?;
 
     // Get the name of the argument
-    let name = match token_stream.advance() {
+    let (name, loc) = match token_stream.advance() {
         Some(op) => match &op.0 {
-            TokenType::Identity(id) => id.to_owned(),
+            TokenType::Identity(id) => (id.to_owned(), op.1.clone()),
             _ => {
-                return Err(ParserError(
+                return Err(ParserError::new(
                     "Unexpected token in place of varible name".to_string(),
                     op.1.clone(),
+                    codes::P0006,
                 ))
             }
         },
         None => {
-            return Err(ParserError(
+            return Err(ParserError::new(
                 "Expected variable name".to_string(),
-                FileLocation::End { file: file.clone() },
+                FileLocation::End { file: *file },
+                codes::P0006,
             ))
         }
     };
@@ -73,5 +75,6 @@ pub fn parse_var_sig(
     return Ok(VarSigniture {
         identity: name,
         argtype,
+        loc,
     });
 }
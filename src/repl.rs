@@ -0,0 +1,272 @@
+use crate::{
+    active_parser::{self, AReplEntry},
+    errors::{codes, source_map, ErrorType, FileLocation, ManagerError},
+    finterpretor::{self, sandbox::Sandbox, ReplState},
+    flat_ir::{self, VNameConverter},
+    lexer::{self, syntax},
+    parser::{self, Program, ReplEntry, Term, Type},
+};
+use std::{
+    fs,
+    io::{stdin, stdout, Write},
+    path::PathBuf,
+    process::exit,
+};
+
+const PROMPT: &str = ">> ";
+const CONTINUE_PROMPT: &str = ".. ";
+const REPL_FILE: &str = "<repl>";
+
+struct Session {
+    program: Program,
+    vars: Vec<(String, Type)>,
+    name_converter: VNameConverter,
+    state: ReplState,
+}
+impl Session {
+    fn new() -> Self {
+        Session {
+            program: Program {
+                structs: Vec::new(),
+                functions: Vec::new(),
+                imports: Vec::new(),
+            },
+            vars: Vec::new(),
+            name_converter: VNameConverter::new(),
+            state: ReplState::new(),
+        }
+    }
+
+    fn eval(&mut self, input: &str, sandbox: &Sandbox) -> Result<(), String> {
+        let entry = parse(input)?;
+
+        if let ReplEntry::Declarations(declarations) = entry {
+            return self.declare(declarations);
+        }
+
+        let (a_program, a_entry) =
+            match active_parser::aparse_repl(&self.program, &self.vars, &entry) {
+                Ok(ok) => ok,
+                Err(err) => return Err(err.report()),
+            };
+
+        let flat_program = flat_ir::flatten_repl(&a_program, &a_entry, &mut self.name_converter);
+        let result = match finterpretor::interpret_repl(
+            &flat_program,
+            &mut self.state,
+            sandbox,
+            &self.name_converter,
+        ) {
+            Ok(ok) => ok,
+            Err(err) => return Err(err.report()),
+        };
+
+        // Remember the variables declared at the top level
+        if let ReplEntry::Terms(block) = entry {
+            for term in block.terms {
+                if let Term::DeclareVar { name, vartype, .. } = term {
+                    self.vars.retain(|(n, _)| *n != name);
+                    self.vars.push((name, vartype));
+                }
+            }
+        }
+
+        if let Some(value) = result.value {
+            println!("{value}");
+        }
+
+        if let Some(code) = result.exit_code {
+            exit(code);
+        }
+
+        return Ok(());
+    }
+
+    fn type_of(&self, input: &str) -> Result<(), String> {
+        let entry = match input.trim().is_empty() {
+            true => None,
+            false => Some(parse(input)?),
+        };
+        let Some(entry @ ReplEntry::Expression(_)) = entry else {
+            return Err(ManagerError::new(
+                "Expected an expression after :type".to_string(),
+                FileLocation::None,
+                codes::M0004,
+            )
+            .report());
+        };
+
+        match active_parser::aparse_repl(&self.program, &self.vars, &entry) {
+            Ok((_, AReplEntry::Expression(expression))) => {
+                println!("{}", expression.get_type().borrow());
+                return Ok(());
+            }
+            Ok(_) => panic!(),
+            Err(err) => return Err(err.report()),
+        }
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let path = PathBuf::from(path);
+        let mut program = match fs::read_to_string(&path) {
+            Ok(program) => program,
+            Err(err) => {
+                return Err(ManagerError::new(
+                    format!("Could not open program file | {err}"),
+                    FileLocation::None,
+                    codes::M0001,
+                )
+                .report())
+            }
+        };
+        program.push(' ');
+        let source = source_map::add_source(path, program.clone());
+
+        let lex_out = match lexer::lex(&program, false, &source, "", &[]) {
+            Ok(lex) => lex,
+            Err(err) => return Err(err.report()),
+        };
+
+        match parser::parse(lex_out, &source) {
+            Ok(parse) => return self.declare(parse),
+            Err(ErrorType::Parser(err)) => return Err(err.report()),
+            Err(ErrorType::Lexer(err)) => return Err(err.report()),
+            Err(_) => panic!(),
+        }
+    }
+
+    // Add declarations to the program, as long as it still checks with them
+    fn declare(&mut self, mut declarations: Program) -> Result<(), String> {
+        let mut program = self.program.clone();
+        program.structs.append(&mut declarations.structs);
+        program.functions.append(&mut declarations.functions);
+
+        let entry = ReplEntry::Declarations(Program {
+            structs: Vec::new(),
+            functions: Vec::new(),
+            imports: Vec::new(),
+        });
+
+        if let Err(err) = active_parser::aparse_repl(&program, &self.vars, &entry) {
+            return Err(err.report());
+        }
+
+        self.program = program;
+        return Ok(());
+    }
+}
+
+fn parse(input: &str) -> Result<ReplEntry, String> {
+    // Allow the final terminator to be left off
+    let mut input = input.trim_end().to_string();
+    if !input.ends_with('~') && !input.ends_with('}') {
+        input.push_str(" ~");
+    }
+
+    // Each entry is its own source, so errors in earlier declarations can
+    // still show the line they were entered on
+    let file = source_map::add_entry(PathBuf::from(REPL_FILE), input.clone());
+
+    let lex_out = match lexer::lex(&input, false, &file, "", &[]) {
+        Ok(lex) => lex,
+        Err(err) => return Err(err.report()),
+    };
+
+    match parser::parse_repl(lex_out, &file) {
+        Ok(entry) => return Ok(entry),
+        Err(ErrorType::Parser(err)) => return Err(err.report()),
+        Err(ErrorType::Lexer(err)) => return Err(err.report()),
+        Err(_) => panic!(),
+    }
+}
+
+// Read one entry, carrying on over more lines while a brace is left open
+fn read_entry() -> Option<String> {
+    let mut input = String::new();
+    let mut prompt = PROMPT;
+
+    loop {
+        print!("{prompt}");
+        let _ = stdout().flush();
+
+        match stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+
+        if !opens_block(&input) {
+            return Some(input);
+        }
+
+        prompt = CONTINUE_PROMPT;
+    }
+}
+
+// Whether a brace outside of strings and comments is left open
+fn opens_block(input: &str) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut comment = false;
+
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, syntax::NEW_LINE) => comment = false,
+            (None, _) if comment => {}
+            (None, syntax::COMMENT) => comment = true,
+            (None, c) if syntax::STRING_QUOTES.contains(c) => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => depth -= 1,
+            _ => {}
+        }
+    }
+
+    return depth > 0;
+}
+
+pub fn repl(sandbox: Sandbox) {
+    let mut session = Session::new();
+
+    while let Some(input) = read_entry() {
+        let input = input.trim();
+
+        let result = match input.split_once(' ').unwrap_or((input, "")) {
+            ("", _) => Ok(()),
+            (":type", expression) => session.type_of(expression),
+            (":load", path) => session.load(path.trim()),
+            (":reset", "") => {
+                session = Session::new();
+                Ok(())
+            }
+            (":quit", "") => break,
+            (command, _) if command.starts_with(':') => Err(ManagerError::new(
+                format!("Unknown command {command}, expected :type, :load, :reset or :quit"),
+                FileLocation::None,
+                codes::M0004,
+            )
+            .report()),
+            _ => session.eval(input, &sandbox),
+        };
+
+        if let Err(err) = result {
+            println!("{err}");
+        }
+    }
+
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braces_in_strings_and_comments_are_ignored() {
+        assert!(!opens_block("cll @println.(\"{\")\n"));
+        assert!(!opens_block("println '{' # {\n"));
+        assert!(opens_block("func null f {\n"));
+        assert!(opens_block("if a { # }\n"));
+        assert!(!opens_block("if a {\n}\n"));
+    }
+}
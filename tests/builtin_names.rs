@@ -0,0 +1,51 @@
+use std::{env, fs, process::Command};
+
+// Error codes reported by `check` for a program declaring `struct <name>`
+fn check_struct(name: &str) -> Vec<String> {
+    let program = format!(
+        "\"{name}\"
+struct {name} {{
+    let str name ~
+}}
+func null @main: str[] args {{
+}}
+"
+    );
+
+    let path = env::temp_dir().join(format!(
+        "termslang-struct-{name}-{}.tms",
+        std::process::id()
+    ));
+    fs::write(&path, program).unwrap();
+
+    let out = Command::new(env!("CARGO_BIN_EXE_termslang"))
+        .arg("check")
+        .arg(&path)
+        .args(["--message-format", "json"])
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    return String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.split("\"code\":\"").nth(1))
+        .map(|code| code[..5].to_string())
+        .collect();
+}
+
+#[test]
+fn process_is_rejected() {
+    assert_eq!(check_struct("process"), ["T0018"]);
+}
+
+#[test]
+fn result_names_are_rejected() {
+    for name in ["fileresult", "textresult", "listresult"] {
+        assert_eq!(check_struct(name), ["T0018"], "{name}");
+    }
+}
+
+#[test]
+fn other_names_are_accepted() {
+    assert_eq!(check_struct("task"), Vec::<String>::new());
+}
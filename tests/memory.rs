@@ -0,0 +1,62 @@
+use std::{env, fs, process::Command};
+
+// Runs `body` 3000 times under a memory limit a leak of one cell per
+// iteration would exceed
+fn run_in_loop(name: &str, body: &str) {
+    let program = format!(
+        "\"{name}\"
+func null @main: str[] args {{
+    let int[][] g = $(0, [0]) int[][] ~
+    let int[] h = $(0, 0) int[] ~
+    loop i: i < 3000 {{
+        let int i = i + 1 ~
+        {body}
+    }}
+    println \"done\" ~
+}}
+"
+    );
+
+    let path = env::temp_dir().join(format!("termslang-{name}-{}.tms", std::process::id()));
+    fs::write(&path, program).unwrap();
+
+    let out = Command::new(env!("CARGO_BIN_EXE_termslang"))
+        .arg("run")
+        .arg(&path)
+        .arg("--max-memory=30000")
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "{name}: {stdout}");
+    assert_eq!(stdout.trim(), "done");
+}
+
+#[test]
+fn append_temporary() {
+    run_in_loop("append", "cll g.@append.([1]) ~ cll g.@remove.(0) ~");
+}
+
+#[test]
+fn insert_temporary() {
+    run_in_loop("insert", "cll g.@insert.(0, [1]) ~ cll g.@remove.(0) ~");
+}
+
+#[test]
+fn extend_temporary() {
+    run_in_loop(
+        "extend",
+        "cll h.@extend.([1, 2]) ~ cll h.@pop.() ~ cll h.@pop.() ~",
+    );
+}
+
+#[test]
+fn contains_temporary() {
+    run_in_loop("contains", "let bool c = g.@contains.([1, 2]) ~");
+}
+
+#[test]
+fn indexof_temporary() {
+    run_in_loop("indexof", "let int c = g.@indexof.([1, 2]) ~");
+}